@@ -0,0 +1,320 @@
+use crate::date::Date;
+use crate::err::did_you_mean;
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+use crate::filters::Filter;
+use crate::parser::Issue;
+use crate::writer::ExportSettings;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/*
+    Columns of the exported issues, shared by every output format.
+    Columns are chosen and ordered with a list such as 'id,title=Summary,state,labels'.
+*/
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+// Fields of an issue that can be exported
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Id,
+    Title,
+    State,
+    StateReason,
+    Created,
+    Updated,
+    Closed,
+    Labels,
+    Assignees,
+    Author,
+    Milestone,
+    Repository,
+    Url,
+    Comments,
+    Reactions,
+    AgeDays,
+    TimeToClose,
+    BusinessDays,
+    BusinessHours,
+    Match,
+}
+
+// Names of the fields in --columns, with their default header
+const FIELDS: [(&str, Field, &str); 20] = [
+    ("id", Field::Id, "ID"),
+    ("title", Field::Title, "Title"),
+    ("state", Field::State, "State"),
+    ("state_reason", Field::StateReason, "State reason"),
+    ("created", Field::Created, "Created at"),
+    ("updated", Field::Updated, "Last update"),
+    ("closed", Field::Closed, "Closed at"),
+    ("labels", Field::Labels, "Labels"),
+    ("assignees", Field::Assignees, "Assignees"),
+    ("author", Field::Author, "Author"),
+    ("milestone", Field::Milestone, "Milestone"),
+    ("repository", Field::Repository, "Repository"),
+    ("url", Field::Url, "URL"),
+    ("comments", Field::Comments, "Comments"),
+    ("reactions", Field::Reactions, "Reactions"),
+    ("age_days", Field::AgeDays, "Age (days)"),
+    ("time_to_close", Field::TimeToClose, "Time to close (days)"),
+    ("business_days", Field::BusinessDays, "Business days open"),
+    (
+        "business_hours",
+        Field::BusinessHours,
+        "Business hours open",
+    ),
+    ("match", Field::Match, "Match"),
+];
+
+impl Field {
+    pub fn parse(name: &str) -> Result<Field, IssueParserErr> {
+        match FIELDS.iter().find(|(id, _, _)| *id == name) {
+            Some((_, field, _)) => Ok(*field),
+            None => {
+                let names: Vec<&str> = FIELDS.iter().map(|(id, _, _)| *id).collect();
+
+                Err(IssueParserErr {
+                    msg: format!(
+                        "'{}': unknown column, expected one of {}.{}",
+                        name,
+                        names.join(", "),
+                        did_you_mean(name, &names)
+                    ),
+                    kind: ErrKind::Input,
+                })
+            }
+        }
+    }
+
+    pub fn from_column(text: &str) -> Option<Field> {
+        // Field of a column of an exported file, from its name or its default header
+        FIELDS
+            .iter()
+            .find(|(id, _, header)| *id == text || *header == text)
+            .map(|(_, field, _)| *field)
+    }
+
+    pub fn name(&self) -> &'static str {
+        FIELDS
+            .iter()
+            .find(|(_, field, _)| field == self)
+            .map_or("", |(id, _, _)| *id)
+    }
+
+    pub fn header(&self) -> &'static str {
+        FIELDS
+            .iter()
+            .find(|(_, field, _)| field == self)
+            .map_or("", |(_, _, header)| *header)
+    }
+
+    pub fn is_business(&self) -> bool {
+        // Fields measured in working time, which require a calendar
+        matches!(self, Field::BusinessDays | Field::BusinessHours)
+    }
+}
+
+// Value of a field, typed for the formats that support it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    Date(Date),
+    List(Vec<String>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Empty => Ok(()),
+            Value::Text(text) => write!(f, "{}", text),
+            Value::Integer(number) => write!(f, "{}", number),
+            Value::Decimal(number) => write!(f, "{:.1}", number),
+            Value::Date(date) => write!(f, "{}", date),
+            Value::List(items) => write!(f, "{}", items.join("; ")),
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Missing values are null and dates are ISO 8601 strings
+        match self {
+            Value::Empty => serializer.serialize_none(),
+            Value::Text(text) => serializer.serialize_str(text),
+            Value::Integer(number) => serializer.serialize_i64(*number),
+            Value::Decimal(number) => serializer.serialize_f64(*number),
+            Value::Date(date) => serializer.collect_str(date),
+            Value::List(items) => items.serialize(serializer),
+        }
+    }
+}
+
+fn rounded(number: f64) -> Value {
+    // Durations are rounded to a tenth, as written in text formats, so that every format agrees
+    Value::Decimal((number * 10.0).round() / 10.0)
+}
+
+fn optional_text(text: Option<&str>) -> Value {
+    match text {
+        Some(text) => Value::Text(String::from(text)),
+        None => Value::Empty,
+    }
+}
+
+// Exported column, a field with its header
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub field: Field,
+    pub header: String,
+}
+
+impl Column {
+    pub fn new(field: Field) -> Column {
+        Column {
+            field,
+            header: String::from(field.header()),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        // Key of the column in structured formats, the name of the field unless the header was renamed
+        if self.header == self.field.header() {
+            self.field.name()
+        } else {
+            &self.header
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Column, IssueParserErr> {
+        // A field name with an optional header, e.g. 'title' or 'title=Summary'
+        let (name, header) = match text.split_once('=') {
+            Some((name, header)) => (name.trim(), Some(header.trim())),
+            None => (text.trim(), None),
+        };
+
+        let field: Field = Field::parse(name)?;
+
+        match header {
+            Some("") => Err(IssueParserErr {
+                msg: format!("'{}': empty column header.", text),
+                kind: ErrKind::Input,
+            }),
+            Some(header) => Ok(Column {
+                field,
+                header: String::from(header),
+            }),
+            None => Ok(Column::new(field)),
+        }
+    }
+
+    pub fn parse_list(text: &str) -> Result<Vec<Column>, IssueParserErr> {
+        // Comma-separated columns, in the order they are exported
+        let mut columns: Vec<Column> = Vec::new();
+
+        for part in text.split(',') {
+            columns.push(Column::parse(part)?);
+        }
+
+        Ok(columns)
+    }
+
+    pub fn defaults(business: bool, snippets: bool) -> Vec<Column> {
+        // Columns exported without --columns, the repository identifies the issues when read back
+        let mut fields: Vec<Field> = vec![
+            Field::Id,
+            Field::Created,
+            Field::Updated,
+            Field::State,
+            Field::Title,
+            Field::Repository,
+        ];
+
+        if business {
+            fields.extend([Field::BusinessDays, Field::BusinessHours]);
+        }
+
+        if snippets {
+            fields.push(Field::Match);
+        }
+
+        fields.into_iter().map(Column::new).collect()
+    }
+
+    pub fn value(&self, issue: &Issue, settings: &ExportSettings, filters: &dyn Filter) -> Value {
+        // Dates are exported in the time zone, durations are measured until the reference instant
+        let timezone = &settings.timezone;
+
+        match self.field {
+            Field::Id => Value::Integer(issue.number as i64),
+            Field::Title => Value::Text(String::from(&issue.title)),
+            Field::State => Value::Text(issue.state.to_string()),
+            Field::StateReason => match issue.state_reason {
+                Some(reason) => Value::Text(reason.to_string()),
+                None => Value::Empty,
+            },
+            Field::Created => Value::Date(issue.created_at.date_in(timezone)),
+            Field::Updated => Value::Date(issue.updated_at.date_in(timezone)),
+            Field::Closed => match issue.closed_at {
+                Some(closed_at) => Value::Date(closed_at.date_in(timezone)),
+                None => Value::Empty,
+            },
+            Field::Labels => Value::List(
+                issue
+                    .labels
+                    .iter()
+                    .map(|label| label.name.clone())
+                    .collect(),
+            ),
+            Field::Assignees => Value::List(
+                issue
+                    .assignees
+                    .iter()
+                    .map(|user| user.login.clone())
+                    .collect(),
+            ),
+            Field::Author => optional_text(issue.user.as_ref().map(|user| user.login.as_str())),
+            Field::Milestone => optional_text(
+                issue
+                    .milestone
+                    .as_ref()
+                    .map(|milestone| milestone.title.as_str()),
+            ),
+            Field::Repository => optional_text(issue.repository().as_deref()),
+            Field::Url => optional_text(issue.url().as_deref()),
+            Field::Comments => Value::Integer(issue.comments.count() as i64),
+            Field::Reactions => Value::Integer(issue.reactions.total_count as i64),
+            Field::AgeDays => Value::Integer(
+                issue
+                    .created_at
+                    .date_in(timezone)
+                    .days_until(&settings.now.date_in(timezone)),
+            ),
+            Field::TimeToClose => match issue.closed_at {
+                Some(closed_at) => {
+                    let days: f64 =
+                        (closed_at.seconds() - issue.created_at.seconds()) as f64 / SECONDS_PER_DAY;
+                    rounded(days)
+                }
+                None => Value::Empty,
+            },
+            Field::BusinessDays | Field::BusinessHours => match &settings.calendar {
+                Some(calendar) => {
+                    let (days, hours) = settings.business_time(calendar, issue);
+
+                    if self.field == Field::BusinessDays {
+                        Value::Integer(days)
+                    } else {
+                        rounded(hours)
+                    }
+                }
+                None => Value::Empty,
+            },
+            Field::Match => optional_text(filters.matched_text(issue).as_deref()),
+        }
+    }
+}
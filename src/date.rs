@@ -1,3 +1,53 @@
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+use crate::timezone::TimeZone;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/*
+    Calendar dates and timestamps.
+    Dates are validated on creation, timestamps are RFC 3339 instants with their UTC offset.
+    User inputs can also be relative date expressions, resolved against a reference date.
+    Durations can be measured in working days and hours with a calendar of weekends and holidays.
+*/
+
+const SECONDS_PER_DAY: i64 = 86400;
+// Durations such as 30d or 1y cannot go further back than this
+const MAX_DURATION_YEARS: i64 = 10000;
+
+fn date_err(msg: String) -> IssueParserErr {
+    IssueParserErr {
+        msg,
+        kind: ErrKind::Date,
+    }
+}
+
+fn parse_number(text: &str, digits: usize) -> Option<i32> {
+    // Parse a fixed-width number made of ASCII digits only
+    if text.len() != digits || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    text.parse().ok()
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i32, month: i32) -> i32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Date {
     pub year: i32,
     pub month: i32,
@@ -5,45 +55,794 @@ pub struct Date {
 }
 
 impl Date {
-    pub fn date_str(date_str: &str) -> Date {
-        // Build a date structure from a string
+    pub fn new(year: i32, month: i32, day: i32) -> Result<Date, IssueParserErr> {
+        // Build a date, rejecting days that do not exist in the calendar
+        if !(1..=12).contains(&month) {
+            return Err(date_err(format!(
+                "{:04}-{:02}-{:02}: month is out of range.",
+                year, month, day
+            )));
+        }
+
+        if day < 1 || day > days_in_month(year, month) {
+            return Err(date_err(format!(
+                "{:04}-{:02}-{:02}: day is out of range.",
+                year, month, day
+            )));
+        }
+
+        Ok(Date { year, month, day })
+    }
+
+    pub fn parse(date_str: &str) -> Result<Date, IssueParserErr> {
+        // Build a date structure from a YYYY-MM-DD string
+        let parts: Vec<&str> = date_str.split('-').collect();
+
+        if parts.len() != 3 {
+            return Err(date_err(format!(
+                "{}: date is not at the right format (YYYY-MM-DD).",
+                date_str
+            )));
+        }
+
+        match (
+            parse_number(parts[0], 4),
+            parse_number(parts[1], 2),
+            parse_number(parts[2], 2),
+        ) {
+            (Some(year), Some(month), Some(day)) => Date::new(year, month, day),
+            _ => Err(date_err(format!(
+                "{}: date is not at the right format (YYYY-MM-DD).",
+                date_str
+            ))),
+        }
+    }
+
+    pub fn date_str(date_str: &str) -> Result<Date, IssueParserErr> {
+        // Build a date structure from a user input
+        // Unbounded dates are represented with the default date
 
         if date_str == "(oldest)" || date_str == "(newest)" {
-            return Date {
-                year: 0,
-                month: 0,
-                day: 0,
-            };
+            return Ok(Date::default());
         }
 
+        Date::parse(date_str)
+    }
+
+    pub fn today(timezone: &TimeZone) -> Date {
+        // Current date in a time zone
+        Timestamp::now().date_in(timezone)
+    }
+
+    pub fn reference(now: &str, timezone: &TimeZone) -> Result<Date, IssueParserErr> {
+        // Date against which relative expressions are resolved, today by default
+        Ok(Timestamp::reference(now)?.date_in(timezone))
+    }
+
+    fn first_of_month(year: i32, month: i32) -> Date {
+        // First day of a month, the month being allowed to overflow the year
+        let index: i32 = year * 12 + (month - 1);
+
         Date {
-            year: date_str[..4].parse().unwrap(),
-            month: date_str[5..7].parse().unwrap(),
-            day: date_str[8..10].parse().unwrap(),
+            year: index.div_euclid(12),
+            month: index.rem_euclid(12) + 1,
+            day: 1,
         }
     }
 
+    pub fn add_months(&self, months: i32) -> Date {
+        // Move by a number of months, keeping the day within the resulting month
+        let first: Date = Date::first_of_month(self.year, self.month + months);
+
+        Date {
+            day: self.day.min(days_in_month(first.year, first.month)),
+            ..first
+        }
+    }
+
+    fn iso_week(year: i32, week: i32) -> Result<(Date, Date), IssueParserErr> {
+        // Monday and Sunday of an ISO 8601 week, the first week containing January 4th
+        let january_4th: Date = Date::new(year, 1, 4)?;
+        let monday: Date = january_4th
+            .add_days(-(january_4th.weekday() as i64))
+            .add_days(7 * (week as i64 - 1));
+
+        // The Thursday of a week always belongs to its ISO year
+        if week < 1 || monday.add_days(3).year != year {
+            return Err(date_err(format!(
+                "{}-W{:02}: week is out of range.",
+                year, week
+            )));
+        }
+
+        Ok((monday, monday.add_days(6)))
+    }
+
+    pub fn resolve(expr: &str, now: &Date) -> Result<(Date, Date), IssueParserErr> {
+        // Resolve a date expression into the first and last days it covers
+        // Expressions are literal dates (2023-06-18), ISO weeks (2026-W14), named periods
+        // (today, yesterday, this-week, last-month, this-quarter, ...) or durations before now (30d, 2w, 6m, 1y)
+        let month_start: Date = Date::first_of_month(now.year, now.month);
+        let quarter_start: Date = Date::first_of_month(now.year, now.month - (now.month - 1) % 3);
+        let week_start: Date = now.add_days(-(now.weekday() as i64));
+        let year_start: Date = Date::first_of_month(now.year, 1);
+
+        let period = |start: Date, months: i32| (start, start.add_months(months).add_days(-1));
+
+        match expr {
+            "today" => return Ok((*now, *now)),
+            "yesterday" => return Ok((now.add_days(-1), now.add_days(-1))),
+            "this-week" => return Ok((week_start, week_start.add_days(6))),
+            "last-week" => return Ok((week_start.add_days(-7), week_start.add_days(-1))),
+            "this-month" => return Ok(period(month_start, 1)),
+            "last-month" => return Ok(period(month_start.add_months(-1), 1)),
+            "this-quarter" => return Ok(period(quarter_start, 3)),
+            "last-quarter" => return Ok(period(quarter_start.add_months(-3), 3)),
+            "this-year" => return Ok(period(year_start, 12)),
+            "last-year" => return Ok(period(year_start.add_months(-12), 12)),
+            _ => (),
+        }
+
+        // ISO week, e.g. 2026-W14
+        if let Some((year, week)) = expr.split_once("-W") {
+            return match (parse_number(year, 4), parse_number(week, 2)) {
+                (Some(year), Some(week)) => Date::iso_week(year, week),
+                _ => Err(date_err(format!(
+                    "{}: week is not at the right format (YYYY-Www).",
+                    expr
+                ))),
+            };
+        }
+
+        // Duration before the reference date, e.g. 30d
+        if let Some(unit) = expr.chars().last().filter(|c| "dwmy".contains(*c)) {
+            let count: &str = &expr[..expr.len() - 1];
+
+            if !count.is_empty() && count.bytes().all(|b| b.is_ascii_digit()) {
+                let too_large = || date_err(format!("{}: duration is too large.", expr));
+                let count: i64 = count.parse().map_err(|_| too_large())?;
+
+                // Days or months before the reference date, bounded to stay within representable dates
+                let (days, months): (i64, i64) = match unit {
+                    'd' => (count, 0),
+                    'w' => (count.checked_mul(7).ok_or_else(too_large)?, 0),
+                    'm' => (0, count),
+                    _ => (0, count.checked_mul(12).ok_or_else(too_large)?),
+                };
+
+                if days > MAX_DURATION_YEARS * 366 || months > MAX_DURATION_YEARS * 12 {
+                    return Err(too_large());
+                }
+
+                let date: Date = now.add_days(-days).add_months(-(months as i32));
+
+                return Ok((date, date));
+            }
+        }
+
+        // Partial dates cover a whole year (2023) or month (2023-06)
+        if let Some(year) = parse_number(expr, 4) {
+            return Ok(period(Date::first_of_month(year, 1), 12));
+        }
+
+        if let Some((year, month)) = expr.split_once('-') {
+            if let (Some(year), Some(month @ 1..=12)) =
+                (parse_number(year, 4), parse_number(month, 2))
+            {
+                return Ok(period(Date::first_of_month(year, month), 1));
+            }
+        }
+
+        // Anything else must be a literal date
+        if expr.len() != 10 {
+            return Err(date_err(format!(
+                "{}: unknown date expression, expected YYYY-MM-DD, YYYY-MM, YYYY, YYYY-Www, today, yesterday, \
+                 this-/last- followed by week, month, quarter or year, or a duration such as 30d.",
+                expr
+            )));
+        }
+
+        let date: Date = Date::parse(expr)?;
+
+        Ok((date, date))
+    }
+
     pub fn compare(&self, date: &Date) -> i32 {
-        // Returns true if the date structure is older than the input
+        // Returns a positive number if the date structure is more recent than the input,
+        // a negative number if it is older and 0 if both are the same day
+        self.cmp(date) as i32
+    }
+
+    pub fn to_days(&self) -> i64 {
+        // Number of days since 1970-01-01
+        let year: i64 = if self.month <= 2 {
+            self.year as i64 - 1
+        } else {
+            self.year as i64
+        };
+        let era: i64 = year.div_euclid(400);
+        let year_of_era: i64 = year - era * 400;
+        let month_index: i64 = (self.month as i64 + 9) % 12;
+        let day_of_year: i64 = (153 * month_index + 2) / 5 + self.day as i64 - 1;
+        let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146097 + day_of_era - 719468
+    }
+
+    pub fn from_days(days: i64) -> Date {
+        // Build the date that is a number of days after 1970-01-01
+        let days: i64 = days + 719468;
+        let era: i64 = days.div_euclid(146097);
+        let day_of_era: i64 = days - era * 146097;
+        let year_of_era: i64 =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year: i64 =
+            day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index: i64 = (5 * day_of_year + 2) / 153;
+        let day: i64 = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month: i64 = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year: i64 = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Date {
+            year: year as i32,
+            month: month as i32,
+            day: day as i32,
+        }
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.to_days() + days)
+    }
+
+    pub fn days_until(&self, date: &Date) -> i64 {
+        // Number of days between the date structure and the input, negative if the input is older
+        date.to_days() - self.to_days()
+    }
+
+    pub fn weekday(&self) -> u32 {
+        // Day of the week, from 0 for Monday to 6 for Sunday
+        // 1970-01-01 was a Thursday
+        (self.to_days() + 3).rem_euclid(7) as u32
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// Instant in time, stored as seconds since the epoch with the UTC offset it was written with
+// Timestamps written as a date alone keep that date in every time zone
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timestamp {
+    seconds: i64,
+    offset: i32,
+    date_only: bool,
+}
+
+impl Timestamp {
+    pub fn from_seconds(seconds: i64, offset: i32) -> Timestamp {
+        Timestamp {
+            seconds,
+            offset,
+            date_only: false,
+        }
+    }
+
+    pub fn from_date(date: &Date) -> Timestamp {
+        // Midnight UTC at the beginning of the date
+        Timestamp {
+            seconds: date.to_days() * SECONDS_PER_DAY,
+            offset: 0,
+            date_only: true,
+        }
+    }
+
+    pub fn now() -> Timestamp {
+        let seconds: i64 = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(_) => 0,
+        };
+
+        Timestamp::from_seconds(seconds, 0)
+    }
+
+    pub fn reference(now: &str) -> Result<Timestamp, IssueParserErr> {
+        // Instant against which ages and relative dates are computed, the current time by default
+        if now == "(today)" {
+            return Ok(Timestamp::now());
+        }
+
+        Timestamp::parse(now)
+    }
+
+    fn parse_offset(text: &str, original: &str) -> Result<i32, IssueParserErr> {
+        // Parse 'Z', '+HH:MM' or '+HHMM' into a number of seconds
+        if text == "Z" || text == "z" {
+            return Ok(0);
+        }
+
+        let sign: i32 = match text.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => return Err(date_err(format!("{}: invalid UTC offset.", original))),
+        };
+
+        let digits: String = text[1..].replace(':', "");
+
+        match (
+            parse_number(digits.get(..2).unwrap_or(""), 2),
+            parse_number(digits.get(2..).unwrap_or(""), 2),
+        ) {
+            (Some(hours), Some(minutes)) if hours < 24 && minutes < 60 => {
+                Ok(sign * (hours * 3600 + minutes * 60))
+            }
+            _ => Err(date_err(format!("{}: invalid UTC offset.", original))),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Timestamp, IssueParserErr> {
+        // Parse an RFC 3339 timestamp such as '2023-05-15T14:39:59Z' or '2023-05-15T16:39:59+02:00'
+        // A date alone is considered as midnight UTC
+        let date: Date = match text.get(..10) {
+            Some(date) => Date::parse(date)?,
+            None => {
+                return Err(date_err(format!(
+                    "{}: timestamp is not at the right format (YYYY-MM-DDTHH:MM:SSZ).",
+                    text
+                )))
+            }
+        };
+
+        let rest: &str = &text[10..];
+
+        if rest.is_empty() {
+            return Ok(Timestamp::from_date(&date));
+        }
+
+        let invalid = || {
+            date_err(format!(
+                "{}: timestamp is not at the right format (YYYY-MM-DDTHH:MM:SSZ).",
+                text
+            ))
+        };
+
+        // The separator between date and time is either 'T' or a space
+        if !matches!(rest.chars().next(), Some('T' | 't' | ' ')) {
+            return Err(invalid());
+        }
+
+        let (time, mut zone): (&str, &str) = match (rest.get(1..9), rest.get(9..)) {
+            (Some(time), Some(zone)) => (time, zone),
+            _ => return Err(invalid()),
+        };
 
-        let mut result: i32 = self.year - date.year;
+        let parts: Vec<&str> = time.split(':').collect();
 
-        if result != 0 {
-            return result;
+        let (hour, minute, second) = match parts[..] {
+            [hour, minute, second] => match (
+                parse_number(hour, 2),
+                parse_number(minute, 2),
+                parse_number(second, 2),
+            ) {
+                (Some(hour), Some(minute), Some(second)) => (hour, minute, second),
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        };
+
+        // Leap seconds are accepted and counted as the last second of the minute
+        if hour > 23 || minute > 59 || second > 60 {
+            return Err(date_err(format!("{}: time is out of range.", text)));
+        }
+
+        // Fractions of seconds are ignored
+        if let Some(fraction) = zone.strip_prefix('.') {
+            let digits: usize = fraction.bytes().take_while(u8::is_ascii_digit).count();
+
+            if digits == 0 {
+                return Err(invalid());
+            }
+
+            zone = &fraction[digits..];
         }
 
-        result = self.month - date.month;
+        let offset: i32 = Timestamp::parse_offset(zone, text)?;
+        let local: i64 = date.to_days() * SECONDS_PER_DAY
+            + (hour as i64) * 3600
+            + (minute as i64) * 60
+            + second.min(59) as i64;
+
+        Ok(Timestamp::from_seconds(local - offset as i64, offset))
+    }
+
+    pub fn seconds(&self) -> i64 {
+        // Number of seconds since 1970-01-01T00:00:00Z
+        self.seconds
+    }
+
+    pub fn offset(&self) -> i32 {
+        // UTC offset in seconds the timestamp was written with
+        self.offset
+    }
+
+    pub fn date(&self) -> Date {
+        // Calendar date at the UTC offset of the timestamp
+        Date::from_days((self.seconds + self.offset as i64).div_euclid(SECONDS_PER_DAY))
+    }
+
+    pub fn utc_date(&self) -> Date {
+        Date::from_days(self.seconds.div_euclid(SECONDS_PER_DAY))
+    }
 
-        if result != 0 {
-            return result;
+    pub fn in_zone(&self, timezone: &TimeZone) -> Timestamp {
+        // Same instant, written with the offset of the time zone at that time
+        if self.date_only {
+            return *self;
         }
 
-        result = self.day - date.day;
+        Timestamp::from_seconds(self.seconds, timezone.offset_at(self.seconds))
+    }
+
+    pub fn date_in(&self, timezone: &TimeZone) -> Date {
+        // Calendar date of the instant in a time zone
+        self.in_zone(timezone).date()
+    }
 
-        if result != 0 {
-            return result;
+    pub fn add_days(&self, days: i64) -> Timestamp {
+        Timestamp {
+            seconds: self.seconds + days * SECONDS_PER_DAY,
+            ..*self
         }
+    }
 
-        result
+    pub fn days_until(&self, timestamp: &Timestamp) -> i64 {
+        // Number of complete days between the timestamp and the input
+        (timestamp.seconds - self.seconds).div_euclid(SECONDS_PER_DAY)
     }
 }
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Timestamp) -> bool {
+        self.seconds == other.seconds
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Timestamp) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Timestamp) -> Ordering {
+        self.seconds.cmp(&other.seconds)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Format the timestamp as RFC 3339, at its own UTC offset
+        let local: i64 = self.seconds + self.offset as i64;
+        let time: i64 = local.rem_euclid(SECONDS_PER_DAY);
+
+        write!(
+            f,
+            "{}T{:02}:{:02}:{:02}",
+            self.date(),
+            time / 3600,
+            (time % 3600) / 60,
+            time % 60
+        )?;
+
+        if self.offset == 0 {
+            return write!(f, "Z");
+        }
+
+        let sign: char = if self.offset < 0 { '-' } else { '+' };
+        let offset: i32 = self.offset.abs();
+
+        write!(
+            f,
+            "{}{:02}:{:02}",
+            sign,
+            offset / 3600,
+            (offset % 3600) / 60
+        )
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Timestamp, D::Error> {
+        let text: String = String::deserialize(deserializer)?;
+        Timestamp::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+// Working days and hours, used to measure durations for service level reports
+#[derive(Clone)]
+pub struct WorkCalendar {
+    // Weekend days, from Monday to Sunday
+    pub weekend: [bool; 7],
+    pub holidays: BTreeSet<Date>,
+    // Working hours, in seconds since midnight
+    pub start_hour: i64,
+    pub end_hour: i64,
+}
+
+impl Default for WorkCalendar {
+    fn default() -> WorkCalendar {
+        // Weekends on Saturday and Sunday, working hours from 9:00 to 17:00
+        WorkCalendar {
+            weekend: [false, false, false, false, false, true, true],
+            holidays: BTreeSet::new(),
+            start_hour: 9 * 3600,
+            end_hour: 17 * 3600,
+        }
+    }
+}
+
+impl WorkCalendar {
+    pub fn new(
+        weekend: &str,
+        hours: &str,
+        holidays: Option<&str>,
+    ) -> Result<WorkCalendar, IssueParserErr> {
+        let (start_hour, end_hour) = WorkCalendar::parse_hours(hours)?;
+
+        Ok(WorkCalendar {
+            weekend: WorkCalendar::parse_weekend(weekend)?,
+            holidays: match holidays {
+                Some(path) => WorkCalendar::load_holidays(path)?,
+                None => BTreeSet::new(),
+            },
+            start_hour,
+            end_hour,
+        })
+    }
+
+    pub fn parse_weekend(text: &str) -> Result<[bool; 7], IssueParserErr> {
+        // Comma-separated list of days such as 'sat,sun' or 'saturday,sunday', or 'none'
+        let days: [&str; 7] = [
+            "monday",
+            "tuesday",
+            "wednesday",
+            "thursday",
+            "friday",
+            "saturday",
+            "sunday",
+        ];
+        let mut weekend: [bool; 7] = [false; 7];
+
+        if text == "none" {
+            return Ok(weekend);
+        }
+
+        for day in text.split(',') {
+            let day: String = day.trim().to_lowercase();
+
+            match days.iter().position(|d| day == *d || day == d[..3]) {
+                Some(index) => weekend[index] = true,
+                None => {
+                    return Err(date_err(format!(
+                        "{}: unknown day of the week, expected mon, tue, wed, thu, fri, sat, sun or none.",
+                        day
+                    )))
+                }
+            }
+        }
+
+        Ok(weekend)
+    }
+
+    pub fn parse_hours(text: &str) -> Result<(i64, i64), IssueParserErr> {
+        // Working hours such as '09:00-17:00'
+        let invalid = || {
+            date_err(format!(
+                "{}: working hours are not at the right format (HH:MM-HH:MM).",
+                text
+            ))
+        };
+
+        let parse_time = |time: &str| -> Option<i64> {
+            let (hours, minutes) = time.trim().split_once(':')?;
+            let hours: i64 = parse_number(hours, 2)? as i64;
+            let minutes: i64 = parse_number(minutes, 2)? as i64;
+
+            if hours > 24 || minutes > 59 || (hours == 24 && minutes > 0) {
+                return None;
+            }
+
+            Some(hours * 3600 + minutes * 60)
+        };
+
+        let (start, end) = text.split_once('-').ok_or_else(invalid)?;
+
+        match (parse_time(start), parse_time(end)) {
+            (Some(start), Some(end)) if start < end => Ok((start, end)),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn parse_ics_date(line: &str) -> Option<Date> {
+        // 'DTSTART;VALUE=DATE:20260101' or 'DTSTART:20260101T000000Z'
+        let value: &str = line.rsplit(':').next()?.trim();
+        let digits: &str = value.get(..8)?;
+
+        // Anything else than ASCII digits is not a date, and could not be sliced
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Date::new(
+            parse_number(&digits[..4], 4)?,
+            parse_number(&digits[4..6], 2)?,
+            parse_number(&digits[6..8], 2)?,
+        )
+        .ok()
+    }
+
+    pub fn parse_holidays(text: &str, name: &str) -> Result<BTreeSet<Date>, IssueParserErr> {
+        // Either an iCalendar file, or one date per line optionally followed by a description
+        let mut holidays: BTreeSet<Date> = BTreeSet::new();
+
+        if text.trim_start().starts_with("BEGIN:VCALENDAR") {
+            let mut start: Option<Date> = None;
+            let mut end: Option<Date> = None;
+
+            for line in text.lines() {
+                if line.starts_with("BEGIN:VEVENT") {
+                    start = None;
+                    end = None;
+                } else if line.starts_with("DTSTART") {
+                    start = WorkCalendar::parse_ics_date(line);
+                } else if line.starts_with("DTEND") {
+                    end = WorkCalendar::parse_ics_date(line);
+                } else if line.starts_with("END:VEVENT") {
+                    // The end of an all-day event is exclusive
+                    if let Some(first) = start {
+                        let last: Date = match end {
+                            Some(end) if end > first => end.add_days(-1),
+                            _ => first,
+                        };
+
+                        for days in 0..=first.days_until(&last) {
+                            holidays.insert(first.add_days(days));
+                        }
+                    }
+                }
+            }
+
+            return Ok(holidays);
+        }
+
+        for (index, line) in text.lines().enumerate() {
+            let line: &str = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.get(..10).map(Date::parse) {
+                Some(Ok(date)) => {
+                    holidays.insert(date);
+                }
+                _ => {
+                    return Err(date_err(format!(
+                        "'{}' line {}: expected a date (YYYY-MM-DD).",
+                        name,
+                        index + 1
+                    )))
+                }
+            }
+        }
+
+        Ok(holidays)
+    }
+
+    pub fn load_holidays(path: &str) -> Result<BTreeSet<Date>, IssueParserErr> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => WorkCalendar::parse_holidays(&text, path),
+            Err(error) => Err(date_err(format!("'{}': {}", path, error))),
+        }
+    }
+
+    pub fn is_working_day(&self, date: &Date) -> bool {
+        !self.weekend[date.weekday() as usize] && !self.holidays.contains(date)
+    }
+
+    pub fn working_days_between(&self, start: &Date, end: &Date) -> i64 {
+        // Number of working days after the start date, up to and including the end date
+        let mut days: i64 = 0;
+        let mut date: Date = start.add_days(1);
+
+        while date <= *end {
+            if self.is_working_day(&date) {
+                days += 1;
+            }
+
+            date = date.add_days(1);
+        }
+
+        days
+    }
+
+    pub fn working_seconds_between(
+        &self,
+        start: &Timestamp,
+        end: &Timestamp,
+        timezone: &TimeZone,
+    ) -> i64 {
+        // Number of seconds within working hours between two instants, in local time
+        let local_start: i64 = start.seconds() + timezone.offset_at(start.seconds()) as i64;
+        let local_end: i64 = end.seconds() + timezone.offset_at(end.seconds()) as i64;
+
+        let mut seconds: i64 = 0;
+        let mut date: Date = Date::from_days(local_start.div_euclid(SECONDS_PER_DAY));
+        let last: Date = Date::from_days(local_end.div_euclid(SECONDS_PER_DAY));
+
+        while date <= last {
+            if self.is_working_day(&date) {
+                let midnight: i64 = date.to_days() * SECONDS_PER_DAY;
+                let from: i64 = local_start.max(midnight + self.start_hour);
+                let to: i64 = local_end.min(midnight + self.end_hour);
+
+                seconds += (to - from).max(0);
+            }
+
+            date = date.add_days(1);
+        }
+
+        seconds
+    }
+}
+
+pub fn parse_age(expr: &str, now: &Date) -> Result<i64, IssueParserErr> {
+    // Number of days in an age such as 90d, 2w, 6m or 1y, counted back from the reference date
+    let is_age = match expr.char_indices().last() {
+        Some((end, unit)) => {
+            "dwmy".contains(unit) && end > 0 && expr[..end].bytes().all(|b| b.is_ascii_digit())
+        }
+        None => false,
+    };
+
+    if !is_age {
+        return Err(date_err(format!(
+            "{}: age is not at the right format, expected a number of days (90d), weeks (2w), months (6m) or years (1y).",
+            expr
+        )));
+    }
+
+    let (start, _) = Date::resolve(expr, now)?;
+
+    Ok(start.days_until(now))
+}
+
+pub fn parse_working_age(
+    expr: &str,
+    now: &Date,
+    calendar: &WorkCalendar,
+) -> Result<i64, IssueParserErr> {
+    // Number of working days in an age: days are working days, e.g. 10d,
+    // weeks, months and years count the working days they span before the reference date
+    let days: i64 = parse_age(expr, now)?;
+
+    if expr.ends_with('d') {
+        return Ok(days);
+    }
+
+    Ok(calendar.working_days_between(&now.add_days(-days), now))
+}
@@ -7,6 +7,7 @@ pub enum ErrKind {
     Parser,
     Writer,
     Get,
+    Date,
 }
 
 #[derive(Debug)]
@@ -30,8 +31,43 @@ impl IssueParserErr {
             ErrKind::Parser => "Parser",
             ErrKind::Writer => "Writer",
             ErrKind::Get => "Get",
+            ErrKind::Date => "Date",
         };
 
         String::from(k)
     }
 }
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    // Levenshtein distance between two strings, counted in characters
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current: Vec<usize> = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+pub fn did_you_mean(value: &str, candidates: &[&str]) -> String {
+    // Suggest the closest candidate to a mistyped value, if it is close enough
+    let closest = candidates
+        .iter()
+        .map(|candidate| (edit_distance(value, candidate), candidate))
+        .min();
+
+    match closest {
+        Some((distance, candidate)) if distance <= 1 + candidate.len() / 3 => {
+            format!(" Did you mean '{}'?", candidate)
+        }
+        _ => String::new(),
+    }
+}
@@ -1,123 +1,1093 @@
+use crate::date::parse_age;
+use crate::date::parse_working_age;
 use crate::date::Date;
+use crate::date::WorkCalendar;
+use crate::err::did_you_mean;
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
 use crate::input::Args;
+use crate::parser::DateField;
 use crate::parser::Issue;
+use crate::parser::IssueState;
+use crate::parser::LabelPattern;
+use crate::parser::Reactions;
+use crate::parser::StateReason;
+use crate::query::parse_number_range;
+use crate::query::Query;
+use crate::query::Range;
+use crate::timezone::TimeZone;
+use regex::Regex;
+use regex::RegexBuilder;
+use std::fmt;
 
 /*
     Filters for the json input.
-    Filter can be either on dates, labels or state.
+    Every filter implements the Filter trait and can be combined with And, Or and Not.
+    Filters are built from user inputs or programmatically with FilterBuilder.
 */
 
-// Primary filters structure
-pub struct Filters {
-    pub label_filter: LabelFilter,
-    pub state_filter: StateFilter,
-    pub date_filter: DateFilter,
+pub trait Filter {
+    // Returns true if the issue passes the filter
+    fn accept(&self, issue: &Issue) -> bool;
+
+    // Human-readable description of the filter, displayed with --verbose
+    fn describe(&self) -> String;
+
+    // Text of the issue matched by the filter, if the filter searches text
+    fn matched_text(&self, _issue: &Issue) -> Option<String> {
+        None
+    }
+
+    // Returns true if the issue is rejected by the filter
+    fn reject(&self, issue: &Issue) -> bool {
+        !self.accept(issue)
+    }
+}
+
+fn describe_operand(filter: &dyn Filter) -> String {
+    // Operands made of several words are parenthesized to show the precedence
+    let description: String = filter.describe();
+
+    if description.contains(' ') {
+        format!("({})", description)
+    } else {
+        description
+    }
+}
+
+// Accepts issues accepted by every filter, or any issue if there is none
+pub struct And(pub Vec<Box<dyn Filter>>);
+
+impl Filter for And {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.0.iter().all(|filter| filter.accept(issue))
+    }
+
+    fn describe(&self) -> String {
+        if self.0.is_empty() {
+            return String::from("any issue");
+        }
+
+        let parts: Vec<String> = self
+            .0
+            .iter()
+            .map(|f| describe_operand(f.as_ref()))
+            .collect();
+        parts.join(" AND ")
+    }
+
+    fn matched_text(&self, issue: &Issue) -> Option<String> {
+        self.0.iter().find_map(|filter| filter.matched_text(issue))
+    }
+}
+
+// Accepts issues accepted by at least one filter
+pub struct Or(pub Vec<Box<dyn Filter>>);
+
+impl Filter for Or {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.0.iter().any(|filter| filter.accept(issue))
+    }
+
+    fn describe(&self) -> String {
+        if self.0.is_empty() {
+            return String::from("no issue");
+        }
+
+        let parts: Vec<String> = self
+            .0
+            .iter()
+            .map(|f| describe_operand(f.as_ref()))
+            .collect();
+        parts.join(" OR ")
+    }
+
+    fn matched_text(&self, issue: &Issue) -> Option<String> {
+        // Only the filters accepting the issue matched its text
+        self.0
+            .iter()
+            .filter(|filter| filter.accept(issue))
+            .find_map(|filter| filter.matched_text(issue))
+    }
+}
+
+// Accepts issues rejected by the filter
+pub struct Not(pub Box<dyn Filter>);
+
+impl Filter for Not {
+    fn accept(&self, issue: &Issue) -> bool {
+        !self.0.accept(issue)
+    }
+
+    fn describe(&self) -> String {
+        format!("NOT {}", describe_operand(self.0.as_ref()))
+    }
+}
+
+// Boolean expression on the labels of an issue
+// ',' is a conjunction of '|' disjunctions, and '!' negates a single label pattern
+#[derive(Debug, Clone, PartialEq)]
+pub enum LabelExpr {
+    Label(LabelPattern),
+    Not(Box<LabelExpr>),
+    And(Vec<LabelExpr>),
+    Or(Vec<LabelExpr>),
+}
+
+fn split_expression(text: &str, separator: char) -> Vec<&str> {
+    // Split the text on a separator, except inside '/.../' regexes
+    // A regex starts with a slash at the beginning of a label, after spaces or '!'
+    let mut parts: Vec<&str> = Vec::new();
+    let mut start: usize = 0;
+    let mut label_start: bool = true;
+    let mut in_regex: bool = false;
+    let mut escaped: bool = false;
+
+    for (i, c) in text.char_indices() {
+        if in_regex {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '/' {
+                in_regex = false;
+            }
+        } else if c == separator {
+            parts.push(&text[start..i]);
+            start = i + c.len_utf8();
+            label_start = true;
+        } else if c == ',' || c == '|' {
+            label_start = true;
+        } else if label_start && c == '/' {
+            in_regex = true;
+            label_start = false;
+        } else if !c.is_whitespace() && c != '!' {
+            label_start = false;
+        }
+    }
+
+    parts.push(&text[start..]);
+    parts
+}
+
+impl LabelExpr {
+    fn parse_label(
+        text: &str,
+        expression: &str,
+        ignore_case: bool,
+    ) -> Result<LabelExpr, IssueParserErr> {
+        // Parse a single label pattern, possibly negated
+        let text = text.trim();
+
+        if let Some(negated) = text.strip_prefix('!') {
+            return Ok(LabelExpr::Not(Box::new(LabelExpr::parse_label(
+                negated,
+                expression,
+                ignore_case,
+            )?)));
+        }
+
+        if text.is_empty() {
+            let e = IssueParserErr {
+                msg: format!("'{}': empty label in expression.", expression),
+                kind: ErrKind::Input,
+            };
+            return Err(e);
+        }
+
+        Ok(LabelExpr::Label(LabelPattern::parse(text, ignore_case)?))
+    }
+
+    pub fn parse(expression: &str, ignore_case: bool) -> Result<LabelExpr, IssueParserErr> {
+        // Parse an expression such as 'bug,!wontfix', 'frontend|backend' or 'area:*,/^prio:(high|critical)$/'
+        // '|' binds tighter than ',', so 'a|b,c' means (a OR b) AND c
+        let mut terms: Vec<LabelExpr> = Vec::new();
+
+        for term in split_expression(expression, ',') {
+            let mut alternatives: Vec<LabelExpr> = Vec::new();
+
+            for label in split_expression(term, '|') {
+                alternatives.push(LabelExpr::parse_label(label, expression, ignore_case)?);
+            }
+
+            if alternatives.len() == 1 {
+                terms.append(&mut alternatives);
+            } else {
+                terms.push(LabelExpr::Or(alternatives));
+            }
+        }
+
+        if terms.len() == 1 {
+            return Ok(terms.remove(0));
+        }
+
+        Ok(LabelExpr::And(terms))
+    }
+
+    fn evaluate(&self, is_labeled: &dyn Fn(&LabelPattern) -> bool) -> bool {
+        // Evaluate the expression given the patterns that are matched
+        match self {
+            LabelExpr::Label(pattern) => is_labeled(pattern),
+            LabelExpr::Not(expr) => !expr.evaluate(is_labeled),
+            LabelExpr::And(exprs) => exprs.iter().all(|expr| expr.evaluate(is_labeled)),
+            LabelExpr::Or(exprs) => exprs.iter().any(|expr| expr.evaluate(is_labeled)),
+        }
+    }
+
+    pub fn matches(&self, issue: &Issue) -> bool {
+        // Evaluate the expression against the labels of the issue
+        self.evaluate(&|pattern| issue.is_labeled(pattern))
+    }
+
+    pub fn matches_name(&self, name: &str) -> bool {
+        // Evaluate the expression against a single label, used to filter label listings
+        self.evaluate(&|pattern| pattern.matches(name))
+    }
+}
+
+impl fmt::Display for LabelExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LabelExpr::Label(pattern) => write!(f, "{}", pattern),
+            LabelExpr::Not(expr) => write!(f, "NOT {}", expr),
+            LabelExpr::And(exprs) => {
+                // Nested disjunctions are parenthesized to show the precedence
+                let parts: Vec<String> = exprs
+                    .iter()
+                    .map(|expr| match expr {
+                        LabelExpr::Or(_) => format!("({})", expr),
+                        _ => expr.to_string(),
+                    })
+                    .collect();
+                write!(f, "{}", parts.join(" AND "))
+            }
+            LabelExpr::Or(exprs) => {
+                let parts: Vec<String> = exprs.iter().map(|expr| expr.to_string()).collect();
+                write!(f, "{}", parts.join(" OR "))
+            }
+        }
+    }
 }
 
 // Structure for labels
+// Repeated --label expressions must all match
 pub struct LabelFilter {
-    pub active: bool,
-    pub pattern: String,
+    pub expression: LabelExpr,
 }
 
 impl LabelFilter {
-    pub fn reject(&self, issue: &Issue) -> bool {
-        // Returns true if the issue is rejected by label filter
+    pub fn new(labels: &[String], ignore_case: bool) -> Result<LabelFilter, IssueParserErr> {
+        // Build the filter from the --label arguments, '(all)' matches any issue
+        let mut exprs: Vec<LabelExpr> = Vec::new();
+
+        for label in labels.iter().filter(|label| *label != "(all)") {
+            match LabelExpr::parse(label, ignore_case)? {
+                LabelExpr::And(mut terms) => exprs.append(&mut terms),
+                expr => exprs.push(expr),
+            }
+        }
+
+        let expression = if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            LabelExpr::And(exprs)
+        };
+
+        Ok(LabelFilter { expression })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // Returns true if the filter accepts any issue
+        self.expression == LabelExpr::And(vec![])
+    }
+
+    pub fn accept_label(&self, name: &str) -> bool {
+        // Returns true if a label should be listed with --print-labels
+        self.is_empty() || self.expression.matches_name(name)
+    }
+}
+
+impl Filter for LabelFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.expression.matches(issue)
+    }
+
+    fn describe(&self) -> String {
+        format!("labels match {}", self.expression)
+    }
+}
+
+// Structure for full-text search
+// Every pattern must match the title, the body or one of the comments of the issue
+pub struct SearchFilter {
+    pub patterns: Vec<Regex>,
+}
+
+// Number of characters kept around a match in snippets
+const SNIPPET_CONTEXT: usize = 30;
+
+fn snippet(text: &str, start: usize, end: usize) -> String {
+    // Extract the match with some context, highlighted between '**'
+    let before: Vec<char> = text[..start].chars().collect();
+    let after: Vec<char> = text[end..].chars().collect();
+    let before_start: usize = before.len().saturating_sub(SNIPPET_CONTEXT);
+    let after_end: usize = after.len().min(SNIPPET_CONTEXT);
+
+    let mut snippet = String::new();
+
+    if before_start > 0 {
+        snippet.push_str("...");
+    }
+
+    snippet.push_str(&before[before_start..].iter().collect::<String>());
+    snippet.push_str(&format!("**{}**", &text[start..end]));
+    snippet.push_str(&after[..after_end].iter().collect::<String>());
+
+    if after_end < after.len() {
+        snippet.push_str("...");
+    }
+
+    // Snippets are kept on a single line
+    snippet.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+impl SearchFilter {
+    pub fn from_text(text: &str) -> Result<SearchFilter, IssueParserErr> {
+        // Plain text is searched as a case-insensitive substring
+        SearchFilter::new(Some(text), None)
+    }
+
+    pub fn new(text: Option<&str>, regex: Option<&str>) -> Result<SearchFilter, IssueParserErr> {
+        // Plain text is searched as a case-insensitive substring, regexes are used as is
+        let mut patterns: Vec<Regex> = Vec::new();
+
+        if let Some(text) = text {
+            match RegexBuilder::new(&regex::escape(text))
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(regex) => patterns.push(regex),
+                Err(error) => {
+                    return Err(IssueParserErr {
+                        msg: format!("'{}': invalid search text: {}", text, error),
+                        kind: ErrKind::Input,
+                    })
+                }
+            }
+        }
+
+        if let Some(regex) = regex {
+            match Regex::new(regex) {
+                Ok(regex) => patterns.push(regex),
+                Err(error) => {
+                    return Err(IssueParserErr {
+                        msg: format!("'{}': invalid search regex: {}", regex, error),
+                        kind: ErrKind::Input,
+                    })
+                }
+            }
+        }
+
+        Ok(SearchFilter { patterns })
+    }
 
-        if !issue.is_labeled(&self.pattern) && self.active {
-            return true;
+    pub fn find(&self, issue: &Issue) -> Option<String> {
+        // Returns a snippet of the first match if every pattern matches the issue
+        // Without patterns, every issue matches an empty text
+        let mut first: Option<String> = None;
+
+        for pattern in &self.patterns {
+            let found = issue.texts().into_iter().find_map(|text| {
+                pattern
+                    .find(text)
+                    .map(|m| snippet(text, m.start(), m.end()))
+            });
+
+            match found {
+                Some(found) => {
+                    first.get_or_insert(found);
+                }
+                None => return None,
+            }
         }
 
-        false
+        Some(first.unwrap_or_default())
+    }
+}
+
+impl Filter for SearchFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.find(issue).is_some()
+    }
+
+    fn describe(&self) -> String {
+        let parts: Vec<String> = self
+            .patterns
+            .iter()
+            .map(|pattern| format!("text matches /{}/", pattern.as_str()))
+            .collect();
+        parts.join(" AND ")
+    }
+
+    fn matched_text(&self, issue: &Issue) -> Option<String> {
+        self.find(issue)
+    }
+}
+
+// Structure for GitHub-style queries
+pub struct QueryFilter {
+    pub query: Query,
+    pub timezone: TimeZone,
+}
+
+impl QueryFilter {
+    pub fn new(
+        query: &str,
+        now: &Date,
+        timezone: &TimeZone,
+        ignore_case: bool,
+    ) -> Result<QueryFilter, IssueParserErr> {
+        Ok(QueryFilter {
+            query: Query::parse(query, now, ignore_case)?,
+            timezone: timezone.clone(),
+        })
+    }
+}
+
+impl Filter for QueryFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.query.matches(issue, &self.timezone)
+    }
+
+    fn describe(&self) -> String {
+        format!("query {}", self.query)
     }
 }
 
 // Structure for state
+// The reason of the last state change can also be required, e.g. closed:not_planned
 pub struct StateFilter {
-    pub active: bool,
-    pub pattern: String,
+    pub state: IssueState,
+    pub reason: Option<StateReason>,
 }
 
+// Values accepted by --state
+pub const STATE_VALUES: [&str; 6] = [
+    "open",
+    "closed",
+    "open:reopened",
+    "closed:completed",
+    "closed:not_planned",
+    "closed:duplicate",
+];
+
 impl StateFilter {
-    pub fn reject(&self, issue: &Issue) -> bool {
-        // Returns true if the issue is rejected by state filter
+    pub fn parse(text: &str) -> Result<StateFilter, IssueParserErr> {
+        // Parse a state with an optional reason, e.g. 'closed:completed'
+        let (state, reason) = match text.split_once(':') {
+            Some((state, reason)) => (state, Some(reason)),
+            None => (text, None),
+        };
+
+        let state_filter = match (IssueState::parse(state), reason.map(StateReason::parse)) {
+            (Ok(state), None) => Some(StateFilter {
+                state,
+                reason: None,
+            }),
+            (Ok(state), Some(Ok(reason))) if reason.state() == state => Some(StateFilter {
+                state,
+                reason: Some(reason),
+            }),
+            _ => None,
+        };
 
-        if (issue.state != self.pattern) && self.active {
-            return true;
+        match state_filter {
+            Some(state_filter) => Ok(state_filter),
+            None => Err(IssueParserErr {
+                msg: format!(
+                    "'{}': unknown state, expected one of {}.{}",
+                    text,
+                    STATE_VALUES.join(", "),
+                    did_you_mean(text, &STATE_VALUES)
+                ),
+                kind: ErrKind::Input,
+            }),
         }
+    }
+}
 
-        false
+impl Filter for StateFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        issue.state == self.state && (self.reason.is_none() || issue.state_reason == self.reason)
+    }
+
+    fn describe(&self) -> String {
+        match self.reason {
+            Some(reason) => format!("state is {} ({})", self.state, reason),
+            None => format!("state is {}", self.state),
+        }
     }
 }
 
-// Structure for state
-pub struct DateFilter {
-    pub start_active: bool,
-    pub start_date: Date,
-    pub end_active: bool,
-    pub end_date: Date,
+// Structure for authors, the issue must be opened by one of them
+pub struct AuthorFilter {
+    pub logins: Vec<String>,
 }
 
-impl DateFilter {
-    pub fn reject(&self, issue: &Issue) -> bool {
-        // Returns true if the issue is out of bounds
+impl Filter for AuthorFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        match &issue.user {
+            Some(user) => self
+                .logins
+                .iter()
+                .any(|login| login.eq_ignore_ascii_case(&user.login)),
+            None => false,
+        }
+    }
 
-        let issue_date: Date = Date::date_str(&issue.updated_at[..10]);
+    fn describe(&self) -> String {
+        format!("author is {}", self.logins.join(" or "))
+    }
+}
 
-        if (self.start_date.compare(&issue_date) > 0) && self.start_active {
-            return true;
-        }
+// Structure for assignees, the issue must be assigned to one of them
+// 'none' stands for unassigned issues
+pub struct AssigneeFilter {
+    pub logins: Vec<String>,
+}
+
+impl Filter for AssigneeFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.logins.iter().any(|login| {
+            if login == "none" {
+                issue.assignees.is_empty()
+            } else {
+                issue
+                    .assignees
+                    .iter()
+                    .any(|user| login.eq_ignore_ascii_case(&user.login))
+            }
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("assignee is {}", self.logins.join(" or "))
+    }
+}
+
+// Structure for milestones, the issue must belong to one of them
+// Titles are compared ignoring case like 'milestone:' queries, 'none' stands for issues without milestone
+pub struct MilestoneFilter {
+    pub titles: Vec<String>,
+}
+
+impl Filter for MilestoneFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.titles
+            .iter()
+            .any(|title| match (&issue.milestone, title.as_str()) {
+                (None, "none") => true,
+                (Some(milestone), _) => milestone.title.eq_ignore_ascii_case(title),
+                (None, _) => false,
+            })
+    }
+
+    fn describe(&self) -> String {
+        format!("milestone is '{}'", self.titles.join("' or '"))
+    }
+}
+
+// Structure for issue numbers, the number must be in one of the ranges
+pub struct NumberFilter {
+    pub ranges: Vec<Range<i64>>,
+}
+
+impl NumberFilter {
+    pub fn parse(ranges: &[String]) -> Result<NumberFilter, IssueParserErr> {
+        // Ranges such as 100..250, 100..*, >=100 or 42
+        let mut parsed: Vec<Range<i64>> = Vec::new();
 
-        if (self.end_date.compare(&issue_date) < 0) && self.end_active {
-            return true;
+        for range in ranges {
+            parsed.push(parse_number_range(range)?);
         }
 
-        false
+        Ok(NumberFilter { ranges: parsed })
     }
 }
 
-impl Filters {
-    pub fn from_args(args: &Args) -> Filters {
-        // Factory function that builds filters from user inputs
-        let label_filter = LabelFilter {
-            active: args.label != "(all)",
-            pattern: String::from(&args.label),
+impl Filter for NumberFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.ranges
+            .iter()
+            .any(|range| range.contains(&(issue.number as i64)))
+    }
+
+    fn describe(&self) -> String {
+        let ranges: Vec<String> = self.ranges.iter().map(|range| range.to_string()).collect();
+        format!("number in {}", ranges.join(" or "))
+    }
+}
+
+// Structure for comments, the issue must have at least this number of comments
+pub struct CommentsFilter {
+    pub min: u32,
+}
+
+impl Filter for CommentsFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        issue.comments.count() >= self.min
+    }
+
+    fn describe(&self) -> String {
+        format!("at least {} comments", self.min)
+    }
+}
+
+// Structure for reactions, the issue must have at least this number of reactions
+// Only the reactions of a single kind are counted if given, e.g. '+1:10'
+pub struct ReactionsFilter {
+    pub reaction: Option<String>,
+    pub min: u32,
+}
+
+impl ReactionsFilter {
+    pub fn parse(text: &str) -> Result<ReactionsFilter, IssueParserErr> {
+        // Minimum number of reactions, optionally prefixed with a kind, e.g. '10' or '+1:10'
+        let (reaction, min) = match text.rsplit_once(':') {
+            Some((reaction, min)) => (Some(reaction), min),
+            None => (None, text),
         };
 
-        let state_filter = StateFilter {
-            active: args.state != "(any)",
-            pattern: String::from(&args.state),
+        if let Some(reaction) = reaction {
+            Reactions::check(reaction)?;
+        }
+
+        match min.parse::<u32>() {
+            Ok(min) => Ok(ReactionsFilter {
+                reaction: reaction.map(String::from),
+                min,
+            }),
+            Err(_) => Err(IssueParserErr {
+                msg: format!(
+                    "'{}': expected a number of reactions, optionally prefixed with a reaction such as '+1:10'.",
+                    text
+                ),
+                kind: ErrKind::Input,
+            }),
+        }
+    }
+}
+
+impl Filter for ReactionsFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        let count: u32 = match &self.reaction {
+            Some(reaction) => issue.reactions.get(reaction).unwrap_or(0),
+            None => issue.reactions.total_count,
         };
 
-        let date_filter = DateFilter {
-            start_active: args.start_date != "(oldest)",
-            start_date: Date::date_str(&args.start_date),
-            end_active: args.end_date != "(newest)",
-            end_date: Date::date_str(&args.end_date),
+        count >= self.min
+    }
+
+    fn describe(&self) -> String {
+        match &self.reaction {
+            Some(reaction) => format!("at least {} '{}' reactions", self.min, reaction),
+            None => format!("at least {} reactions", self.min),
+        }
+    }
+}
+
+// Structure for dates
+// The timestamp of the issue is converted to a date in the time zone before being compared
+// Issues without the timestamp, e.g. open issues for the closing date, are rejected
+pub struct DateFilter {
+    pub timezone: TimeZone,
+    pub field: DateField,
+    pub range: Range<Date>,
+}
+
+impl Filter for DateFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        match issue.timestamp(self.field) {
+            Some(timestamp) => self.range.contains(&timestamp.date_in(&self.timezone)),
+            None => false,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{} date in {}", self.field, self.range)
+    }
+}
+
+// Structure for ages, in days between a timestamp of the issue and the reference date
+// Issues without the timestamp are rejected
+pub struct AgeFilter {
+    pub timezone: TimeZone,
+    pub now: Date,
+    pub field: DateField,
+    pub range: Range<i64>,
+    // Ages are counted in working days with a calendar
+    pub calendar: Option<WorkCalendar>,
+}
+
+impl AgeFilter {
+    pub fn age(&self, issue: &Issue) -> Option<i64> {
+        issue.timestamp(self.field).map(|timestamp| {
+            let date: Date = timestamp.date_in(&self.timezone);
+
+            match &self.calendar {
+                Some(calendar) => calendar.working_days_between(&date, &self.now),
+                None => date.days_until(&self.now),
+            }
+        })
+    }
+}
+
+impl Filter for AgeFilter {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.age(issue).is_some_and(|age| self.range.contains(&age))
+    }
+
+    fn describe(&self) -> String {
+        let unit: &str = match self.calendar {
+            Some(_) => "working days",
+            None => "days",
         };
 
+        format!("{} {} {} ago", self.field, self.range, unit)
+    }
+}
+
+// Builds filters programmatically, every filter added must accept an issue
+// The time zone, reference date, working calendar and case sensitivity apply to the filters added afterwards
+pub struct FilterBuilder {
+    filters: Vec<Box<dyn Filter>>,
+    timezone: TimeZone,
+    now: Date,
+    calendar: Option<WorkCalendar>,
+    ignore_case: bool,
+}
+
+impl Default for FilterBuilder {
+    fn default() -> FilterBuilder {
+        FilterBuilder {
+            filters: Vec::new(),
+            timezone: TimeZone::utc(),
+            now: Date::today(&TimeZone::utc()),
+            calendar: None,
+            ignore_case: false,
+        }
+    }
+}
+
+impl FilterBuilder {
+    pub fn new() -> FilterBuilder {
+        FilterBuilder::default()
+    }
+
+    pub fn timezone(mut self, timezone: TimeZone) -> FilterBuilder {
+        self.timezone = timezone;
+        self
+    }
+
+    pub fn calendar(mut self, calendar: WorkCalendar) -> FilterBuilder {
+        // Count ages in working days, skipping weekends and holidays
+        self.calendar = Some(calendar);
+        self
+    }
+
+    pub fn now(mut self, now: Date) -> FilterBuilder {
+        // Reference date of the relative dates in queries
+        self.now = now;
+        self
+    }
+
+    pub fn ignore_case(mut self, ignore_case: bool) -> FilterBuilder {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    pub fn filter(mut self, filter: impl Filter + 'static) -> FilterBuilder {
+        // Add any filter, including user-defined ones
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    pub fn labels(self, labels: &[String]) -> Result<FilterBuilder, IssueParserErr> {
+        // Label expressions such as 'bug,!wontfix', ignored if there is none
+        let label_filter: LabelFilter = LabelFilter::new(labels, self.ignore_case)?;
+
+        if label_filter.is_empty() {
+            return Ok(self);
+        }
+
+        Ok(self.filter(label_filter))
+    }
+
+    pub fn state(self, state: &str) -> Result<FilterBuilder, IssueParserErr> {
+        // State with an optional reason, e.g. 'open' or 'closed:not_planned'
+        Ok(self.filter(StateFilter::parse(state)?))
+    }
+
+    pub fn dates(self, field: DateField, start: Option<Date>, end: Option<Date>) -> FilterBuilder {
+        // Bounds are inclusive, a missing bound is unlimited
+        let timezone: TimeZone = self.timezone.clone();
+
+        self.filter(DateFilter {
+            timezone,
+            field,
+            range: Range {
+                min: start,
+                max: end,
+            },
+        })
+    }
+
+    fn ages(self, field: DateField, range: Range<i64>) -> FilterBuilder {
+        let timezone: TimeZone = self.timezone.clone();
+        let now: Date = self.now;
+        let calendar: Option<WorkCalendar> = self.calendar.clone();
+
+        self.filter(AgeFilter {
+            timezone,
+            now,
+            field,
+            range,
+            calendar,
+        })
+    }
+
+    fn age_days(&self, age: &str) -> Result<i64, IssueParserErr> {
+        // Days of an age, working days with a calendar
+        match &self.calendar {
+            Some(calendar) => parse_working_age(age, &self.now, calendar),
+            None => parse_age(age, &self.now),
+        }
+    }
+
+    pub fn stale(self, age: &str) -> Result<FilterBuilder, IssueParserErr> {
+        // Issues not updated for at least the age, e.g. 90d
+        let days: i64 = self.age_days(age)?;
+
+        Ok(self.ages(
+            DateField::Updated,
+            Range {
+                min: Some(days),
+                max: None,
+            },
+        ))
+    }
+
+    pub fn older_than(self, age: &str) -> Result<FilterBuilder, IssueParserErr> {
+        // Issues created strictly before the age, e.g. 30d
+        let days: i64 = self.age_days(age)?;
+
+        Ok(self.ages(
+            DateField::Created,
+            Range {
+                min: Some(days + 1),
+                max: None,
+            },
+        ))
+    }
+
+    pub fn younger_than(self, age: &str) -> Result<FilterBuilder, IssueParserErr> {
+        // Issues created strictly after the age, e.g. 7d
+        let days: i64 = self.age_days(age)?;
+
+        Ok(self.ages(
+            DateField::Created,
+            Range {
+                min: None,
+                max: Some(days - 1),
+            },
+        ))
+    }
+
+    pub fn search(self, text: &str) -> Result<FilterBuilder, IssueParserErr> {
+        // Case-insensitive substring of the title, body or comments
+        Ok(self.filter(SearchFilter::from_text(text)?))
+    }
+
+    pub fn search_regex(self, regex: &str) -> Result<FilterBuilder, IssueParserErr> {
+        Ok(self.filter(SearchFilter::new(None, Some(regex))?))
+    }
+
+    pub fn authors(self, logins: &[String]) -> FilterBuilder {
+        // Issues opened by any of the users, ignored if there is none
+        if logins.is_empty() {
+            return self;
+        }
+
+        self.filter(AuthorFilter {
+            logins: logins.to_vec(),
+        })
+    }
+
+    pub fn assignees(self, logins: &[String]) -> FilterBuilder {
+        // Issues assigned to any of the users, or unassigned with 'none'
+        if logins.is_empty() {
+            return self;
+        }
+
+        self.filter(AssigneeFilter {
+            logins: logins.to_vec(),
+        })
+    }
+
+    pub fn milestones(self, titles: &[String]) -> FilterBuilder {
+        // Issues in any of the milestones, or without milestone with 'none'
+        if titles.is_empty() {
+            return self;
+        }
+
+        self.filter(MilestoneFilter {
+            titles: titles.to_vec(),
+        })
+    }
+
+    pub fn numbers(self, ranges: &[String]) -> Result<FilterBuilder, IssueParserErr> {
+        // Issues whose number is in any of the ranges
+        if ranges.is_empty() {
+            return Ok(self);
+        }
+
+        Ok(self.filter(NumberFilter::parse(ranges)?))
+    }
+
+    pub fn min_comments(self, min: u32) -> FilterBuilder {
+        self.filter(CommentsFilter { min })
+    }
+
+    pub fn min_reactions(self, min: &str) -> Result<FilterBuilder, IssueParserErr> {
+        // Minimum number of reactions, of any kind ('10') or of a single kind ('+1:10')
+        Ok(self.filter(ReactionsFilter::parse(min)?))
+    }
+
+    pub fn query(self, query: &str) -> Result<FilterBuilder, IssueParserErr> {
+        let query_filter = QueryFilter::new(query, &self.now, &self.timezone, self.ignore_case)?;
+
+        Ok(self.filter(query_filter))
+    }
+
+    pub fn build(self) -> Filters {
         Filters {
-            label_filter,
-            state_filter,
-            date_filter,
+            filters: self.filters,
         }
     }
+}
+
+// Primary filters structure, an issue must be accepted by every filter
+pub struct Filters {
+    pub filters: Vec<Box<dyn Filter>>,
+}
+
+impl Filters {
+    pub fn builder() -> FilterBuilder {
+        FilterBuilder::new()
+    }
+
+    pub fn from_args(args: &Args) -> Result<Filters, IssueParserErr> {
+        // Factory function that builds filters from user inputs
+        // Relative dates are resolved against the reference date
+        // The start bound is the first day of the expression, the end bound its last day
+        // Each bound can target another field than --date-field with a prefix, e.g. 'closed:30d'
+        let timezone: TimeZone = TimeZone::parse(&args.timezone)?;
+        let now: Date = Date::reference(&args.now, &timezone)?;
+        // Changelogs list the issues closed within the dates
+        let date_field: DateField = if args.changelog {
+            DateField::Closed
+        } else {
+            DateField::from_arg(&args.date_field)?
+        };
+
+        let mut builder: FilterBuilder = Filters::builder()
+            .timezone(timezone)
+            .now(now)
+            .ignore_case(args.ignore_case)
+            .labels(&args.label)?;
+
+        // Ages are measured in working days along with the exported durations
+        if args.business_time {
+            builder = builder.calendar(WorkCalendar::new(
+                &args.weekend,
+                &args.working_hours,
+                args.holidays.as_deref(),
+            )?);
+        }
+
+        if args.state != "(any)" {
+            builder = builder.state(&args.state)?;
+        }
+
+        builder = builder
+            .authors(&args.author)
+            .assignees(&args.assignee)
+            .milestones(&args.milestone)
+            .numbers(&args.number)?;
 
-    pub fn reject(&self, issue: &Issue) -> bool {
-        // Returns true if the issue is rejected by the filters
+        if args.start_date != "(oldest)" {
+            let (field, expr) = DateField::split(&args.start_date, date_field)?;
+            builder = builder.dates(field, Some(Date::resolve(expr, &now)?.0), None);
+        }
+
+        if args.end_date != "(newest)" {
+            let (field, expr) = DateField::split(&args.end_date, date_field)?;
+            builder = builder.dates(field, None, Some(Date::resolve(expr, &now)?.1));
+        }
 
-        if self.label_filter.reject(issue) {
-            return true;
+        if let Some(min) = args.min_comments {
+            builder = builder.min_comments(min);
         }
 
-        if self.state_filter.reject(issue) {
-            return true;
+        if let Some(min) = &args.min_reactions {
+            builder = builder.min_reactions(min)?;
         }
 
-        if self.date_filter.reject(issue) {
-            return true;
+        if let Some(age) = &args.stale {
+            builder = builder.stale(age)?;
         }
 
-        false
+        if let Some(age) = &args.older_than {
+            builder = builder.older_than(age)?;
+        }
+
+        if let Some(age) = &args.younger_than {
+            builder = builder.younger_than(age)?;
+        }
+
+        if let Some(text) = &args.search {
+            builder = builder.search(text)?;
+        }
+
+        if let Some(regex) = &args.search_regex {
+            builder = builder.search_regex(regex)?;
+        }
+
+        if let Some(query) = &args.query {
+            builder = builder.query(query)?;
+        }
+
+        Ok(builder.build())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+}
+
+impl Filter for Filters {
+    fn accept(&self, issue: &Issue) -> bool {
+        self.filters.iter().all(|filter| filter.accept(issue))
+    }
+
+    fn describe(&self) -> String {
+        let descriptions: Vec<String> = self.filters.iter().map(|f| f.describe()).collect();
+        descriptions.join("\n")
+    }
+
+    fn matched_text(&self, issue: &Issue) -> Option<String> {
+        self.filters
+            .iter()
+            .find_map(|filter| filter.matched_text(issue))
     }
 }
@@ -0,0 +1,138 @@
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+use std::path::Path;
+
+/*
+    Minimal glob support used to expand input patterns.
+    Supported wildcards are '*', '?' and character classes such as '[abc]' or '[a-z]'.
+*/
+
+pub fn is_pattern(text: &str) -> bool {
+    // Returns true if the text contains at least one wildcard
+    text.contains(['*', '?', '['])
+}
+
+fn match_class(class: &[char], c: char) -> bool {
+    // Match a single character against the content of a '[...]' class
+    let (negated, class) = match class.first() {
+        Some('!') | Some('^') => (true, &class[1..]),
+        _ => (false, class),
+    };
+
+    let mut found = false;
+    let mut i = 0;
+
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            if class[i] <= c && c <= class[i + 2] {
+                found = true;
+            }
+            i += 3;
+        } else {
+            if class[i] == c {
+                found = true;
+            }
+            i += 1;
+        }
+    }
+
+    found != negated
+}
+
+fn match_chars(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => {
+            // Try every possible length for the star, shortest first
+            (0..=text.len()).any(|n| match_chars(&pattern[1..], &text[n..]))
+        }
+        Some('?') => !text.is_empty() && match_chars(&pattern[1..], &text[1..]),
+        Some('[') => {
+            // An unterminated class is matched literally
+            match pattern.iter().skip(1).position(|&c| c == ']') {
+                Some(end) if end > 0 => {
+                    !text.is_empty()
+                        && match_class(&pattern[1..=end], text[0])
+                        && match_chars(&pattern[end + 2..], &text[1..])
+                }
+                _ => text.first() == Some(&'[') && match_chars(&pattern[1..], &text[1..]),
+            }
+        }
+        Some(&c) => text.first() == Some(&c) && match_chars(&pattern[1..], &text[1..]),
+    }
+}
+
+pub fn matches(pattern: &str, text: &str) -> bool {
+    // Returns true if the whole text is matched by the glob pattern
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    match_chars(&pattern, &text)
+}
+
+pub fn expand(pattern: &str) -> Result<Vec<String>, IssueParserErr> {
+    // Expand a path pattern into the sorted list of existing paths it matches
+    let mut paths: Vec<String> = vec![];
+    let mut components = pattern.split('/').peekable();
+
+    // Absolute patterns start from the root directory
+    if pattern.starts_with('/') {
+        components.next();
+        paths.push(String::from("/"));
+    } else {
+        paths.push(String::new());
+    }
+
+    while let Some(component) = components.next() {
+        let is_last = components.peek().is_none();
+        let mut next_paths: Vec<String> = vec![];
+
+        for path in &paths {
+            if !is_pattern(component) {
+                next_paths.push(format!("{}{}", path, component));
+                continue;
+            }
+
+            let dir = if path.is_empty() { "." } else { path.as_str() };
+
+            let entries = match std::fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+
+                // Hidden files are only matched by patterns explicitly starting with a dot
+                if name.starts_with('.') && !component.starts_with('.') {
+                    continue;
+                }
+
+                if matches(component, &name) {
+                    next_paths.push(format!("{}{}", path, name));
+                }
+            }
+        }
+
+        if !is_last {
+            next_paths.retain(|p| p.is_empty() || Path::new(p).is_dir());
+            for path in &mut next_paths {
+                path.push('/');
+            }
+        }
+
+        paths = next_paths;
+    }
+
+    paths.retain(|p| Path::new(p).exists());
+    paths.sort();
+
+    if paths.is_empty() {
+        return Err(IssueParserErr {
+            msg: format!("'{}' did not match any file.", pattern),
+            kind: ErrKind::Input,
+        });
+    }
+
+    Ok(paths)
+}
@@ -0,0 +1,487 @@
+use crate::columns::Field;
+use crate::columns::Value;
+use crate::date::Date;
+use crate::filters::Filter;
+use crate::parser::collect_repo_labels;
+use crate::parser::Issue;
+use crate::parser::IssueState;
+use crate::parser::Label;
+use crate::parser::LabelStats;
+use crate::writer::ExportSettings;
+
+/*
+    Self-contained HTML report, to share issues with people who would rather not open a csv.
+    The page holds summary counts by state and label, SVG charts and a sortable, filterable table,
+    with its styles and scripts inlined so that it can be opened without network access.
+*/
+
+// Colors of the open and closed states on GitHub
+const OPEN_COLOR: &str = "#1a7f37";
+const CLOSED_COLOR: &str = "#8250df";
+// Labels without a valid color are drawn in the default gray of GitHub
+const DEFAULT_LABEL_COLOR: &str = "ededed";
+
+// Size of the timeline, and maximum number of points drawn on it
+const CHART_WIDTH: i64 = 720;
+const CHART_HEIGHT: i64 = 240;
+const CHART_MARGIN: i64 = 40;
+const MAX_POINTS: i64 = 120;
+// Height of a bar in the label chart, and maximum number of labels drawn
+const BAR_HEIGHT: i64 = 22;
+const MAX_BARS: usize = 20;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; color: #1f2328; margin: 2em auto; max-width: 1200px; padding: 0 1em; }
+h1 { font-size: 1.6em; margin-bottom: 0.2em; }
+h2 { font-size: 1.2em; border-bottom: 1px solid #d0d7de; padding-bottom: 0.3em; margin-top: 1.6em; }
+.generated { color: #59636e; margin-top: 0; }
+.cards { display: flex; gap: 1em; flex-wrap: wrap; }
+.card { border: 1px solid #d0d7de; border-radius: 6px; padding: 0.8em 1.2em; min-width: 8em; }
+.card .count { font-size: 2em; font-weight: 600; }
+.card.open .count { color: #1a7f37; }
+.card.closed .count { color: #8250df; }
+.label { display: inline-block; border-radius: 2em; padding: 0 0.6em; margin: 0.1em; font-size: 0.85em; font-weight: 500; line-height: 1.6em; white-space: nowrap; }
+svg text { font-size: 11px; fill: #59636e; }
+#filter { width: 100%; box-sizing: border-box; padding: 0.5em; margin-bottom: 0.8em; border: 1px solid #d0d7de; border-radius: 6px; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #d0d7de; padding: 0.4em 0.6em; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; cursor: pointer; user-select: none; position: sticky; top: 0; }
+th.asc::after { content: " \25B2"; }
+th.desc::after { content: " \25BC"; }
+a { color: #0969da; text-decoration: none; }
+"#;
+
+// Sort the table when a header is clicked, and hide the rows not containing the filter text
+const SCRIPT: &str = r##"
+document.querySelectorAll("#issues th").forEach(function (header, index) {
+  header.addEventListener("click", function () {
+    var body = document.querySelector("#issues tbody");
+    var ascending = !header.classList.contains("asc");
+    document.querySelectorAll("#issues th").forEach(function (other) { other.classList.remove("asc", "desc"); });
+    header.classList.add(ascending ? "asc" : "desc");
+    var rows = Array.prototype.slice.call(body.rows);
+    rows.sort(function (a, b) {
+      var x = a.cells[index].getAttribute("data-value");
+      var y = b.cells[index].getAttribute("data-value");
+      var order = (x !== "" && y !== "" && !isNaN(x) && !isNaN(y)) ? x - y : x.localeCompare(y);
+      return ascending ? order : -order;
+    });
+    rows.forEach(function (row) { body.appendChild(row); });
+  });
+});
+document.getElementById("filter").addEventListener("input", function (event) {
+  var text = event.target.value.toLowerCase();
+  var shown = 0;
+  document.querySelectorAll("#issues tbody tr").forEach(function (row) {
+    var visible = row.textContent.toLowerCase().indexOf(text) !== -1;
+    row.style.display = visible ? "" : "none";
+    if (visible) { shown += 1; }
+  });
+  document.getElementById("shown").textContent = shown;
+});
+"##;
+
+pub fn escape(text: &str) -> String {
+    // Escape the HTML special characters, in text and in attribute values
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+pub fn label_colors(color: Option<&str>) -> (String, &'static str) {
+    // Background of a label chip, with a text color readable on it
+    let color: &str = match color {
+        Some(color) if color.len() == 6 && color.chars().all(|c| c.is_ascii_hexdigit()) => color,
+        _ => DEFAULT_LABEL_COLOR,
+    };
+
+    let channel = |index: usize| i64::from_str_radix(&color[index..index + 2], 16).unwrap_or(0);
+    // Perceived brightness, from 0 to 255
+    let brightness: i64 = (channel(0) * 299 + channel(2) * 587 + channel(4) * 114) / 1000;
+
+    let text: &str = if brightness > 150 {
+        "#1f2328"
+    } else {
+        "#ffffff"
+    };
+
+    (format!("#{}", color.to_lowercase()), text)
+}
+
+fn label_chip(name: &str, color: Option<&str>) -> String {
+    let (background, text) = label_colors(color);
+
+    format!(
+        r#"<span class="label" style="background-color: {}; color: {}">{}</span>"#,
+        background,
+        text,
+        escape(name)
+    )
+}
+
+fn label_chips(labels: &[Label]) -> String {
+    labels
+        .iter()
+        .map(|label| label_chip(&label.name, label.color.as_deref()))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn sort_value(value: &Value) -> String {
+    // Value compared when sorting a column: numbers, ISO dates or lowercase text
+    match value {
+        Value::Empty => String::new(),
+        Value::Integer(number) => number.to_string(),
+        Value::Decimal(number) => number.to_string(),
+        Value::Date(date) => date.to_string(),
+        Value::Text(_) | Value::List(_) => value.to_string().to_lowercase(),
+    }
+}
+
+fn table_cell(issue: &Issue, field: Field, value: &Value) -> String {
+    // Issue numbers link to their page and labels are drawn as chips
+    // Other URLs than http(s) ones are written as text
+    let content: String = match (field, issue.link()) {
+        (Field::Id, Some(url)) => format!(r#"<a href="{}">#{}</a>"#, escape(&url), issue.number),
+        (Field::Url, Some(url)) => format!(r#"<a href="{}">{}</a>"#, escape(&url), escape(&url)),
+        (Field::Labels, _) => label_chips(&issue.labels),
+        _ => escape(&value.to_string()),
+    };
+
+    format!(
+        r#"<td data-value="{}">{}</td>"#,
+        escape(&sort_value(value)),
+        content
+    )
+}
+
+fn issues_table(issues: &[Issue], filters: &dyn Filter, settings: &ExportSettings) -> String {
+    let mut table = String::from(r#"<table id="issues"><thead><tr>"#);
+
+    for column in &settings.columns {
+        table.push_str(&format!("<th>{}</th>", escape(&column.header)));
+    }
+
+    table.push_str("</tr></thead><tbody>");
+
+    for issue in issues {
+        table.push_str("<tr>");
+
+        for column in &settings.columns {
+            let value: Value = column.value(issue, settings, filters);
+            table.push_str(&table_cell(issue, column.field, &value));
+        }
+
+        table.push_str("</tr>");
+    }
+
+    table.push_str("</tbody></table>");
+    table
+}
+
+fn state_cards(issues: &[Issue]) -> String {
+    let open: usize = issues
+        .iter()
+        .filter(|issue| issue.state == IssueState::Open)
+        .count();
+
+    format!(
+        r#"<div class="cards"><div class="card"><div class="count">{}</div>issues</div><div class="card open"><div class="count">{}</div>open</div><div class="card closed"><div class="count">{}</div>closed</div></div>"#,
+        issues.len(),
+        open,
+        issues.len() - open
+    )
+}
+
+// Number of open and closed issues at a date
+#[derive(Debug, PartialEq)]
+pub struct TimelinePoint {
+    pub date: Date,
+    pub open: usize,
+    pub closed: usize,
+}
+
+pub fn timeline(issues: &[Issue], settings: &ExportSettings) -> Vec<TimelinePoint> {
+    // Count the issues open and closed at evenly spaced dates, from the first creation to the reference date
+    let timezone = &settings.timezone;
+    let now: Date = settings.now.date_in(timezone);
+
+    let first: Date = match issues
+        .iter()
+        .map(|issue| issue.created_at.date_in(timezone))
+        .min_by_key(|date| date.to_days())
+    {
+        Some(first) if first.to_days() <= now.to_days() => first,
+        _ => return Vec::new(),
+    };
+
+    let span: i64 = first.days_until(&now);
+    // Round up so that there are never more points than the maximum
+    let step: i64 = (span + MAX_POINTS - 1) / MAX_POINTS;
+    let step: i64 = step.max(1);
+
+    let mut dates: Vec<Date> = (0..=span / step)
+        .map(|index| first.add_days(index * step))
+        .collect();
+
+    if dates
+        .last()
+        .is_some_and(|last| last.to_days() < now.to_days())
+    {
+        dates.push(now);
+    }
+
+    dates
+        .into_iter()
+        .map(|date| {
+            let mut point = TimelinePoint {
+                date,
+                open: 0,
+                closed: 0,
+            };
+
+            for issue in issues {
+                if issue.created_at.date_in(timezone).to_days() > date.to_days() {
+                    continue;
+                }
+
+                match issue.closed_at {
+                    Some(closed_at) if closed_at.date_in(timezone).to_days() <= date.to_days() => {
+                        point.closed += 1
+                    }
+                    _ => point.open += 1,
+                }
+            }
+
+            point
+        })
+        .collect()
+}
+
+fn timeline_chart(points: &[TimelinePoint]) -> String {
+    // Lines of the open and closed issues over time
+    if points.is_empty() {
+        return String::from("<p>No issues to chart.</p>");
+    }
+
+    let maximum: usize = points
+        .iter()
+        .map(|point| point.open.max(point.closed))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let plot_width: i64 = CHART_WIDTH - 2 * CHART_MARGIN;
+    let plot_height: i64 = CHART_HEIGHT - 2 * CHART_MARGIN;
+    let last: i64 = (points.len() as i64 - 1).max(1);
+
+    let x = |index: usize| CHART_MARGIN + index as i64 * plot_width / last;
+    let y = |count: usize| CHART_MARGIN + plot_height - count as i64 * plot_height / maximum as i64;
+
+    let line = |count: fn(&TimelinePoint) -> usize, color: &str| {
+        let coordinates: Vec<String> = points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| format!("{},{}", x(index), y(count(point))))
+            .collect();
+
+        format!(
+            r#"<polyline fill="none" stroke="{}" stroke-width="2" points="{}"/>"#,
+            color,
+            coordinates.join(" ")
+        )
+    };
+
+    let bottom: i64 = CHART_MARGIN + plot_height;
+    let right: i64 = CHART_MARGIN + plot_width;
+
+    let mut svg: String = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" role="img" aria-label="Open and closed issues over time">"#,
+        CHART_WIDTH, CHART_HEIGHT, CHART_WIDTH, CHART_HEIGHT
+    );
+
+    // Axes, with the extreme counts and dates
+    svg.push_str(&format!(
+        r##"<line x1="{m}" y1="{m}" x2="{m}" y2="{b}" stroke="#d0d7de"/><line x1="{m}" y1="{b}" x2="{r}" y2="{b}" stroke="#d0d7de"/>"##,
+        m = CHART_MARGIN,
+        b = bottom,
+        r = right
+    ));
+    svg.push_str(&format!(
+        r#"<text x="{}" y="{}" text-anchor="end">{}</text><text x="{}" y="{}" text-anchor="end">0</text>"#,
+        CHART_MARGIN - 6,
+        CHART_MARGIN + 4,
+        maximum,
+        CHART_MARGIN - 6,
+        bottom + 4
+    ));
+    svg.push_str(&format!(
+        r#"<text x="{}" y="{}">{}</text><text x="{}" y="{}" text-anchor="end">{}</text>"#,
+        CHART_MARGIN,
+        bottom + 16,
+        points[0].date,
+        right,
+        bottom + 16,
+        points[points.len() - 1].date
+    ));
+
+    svg.push_str(&line(|point| point.open, OPEN_COLOR));
+    svg.push_str(&line(|point| point.closed, CLOSED_COLOR));
+
+    // Legend
+    for (index, (name, color)) in [("open", OPEN_COLOR), ("closed", CLOSED_COLOR)]
+        .iter()
+        .enumerate()
+    {
+        let left: i64 = CHART_MARGIN + index as i64 * 80;
+        svg.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="10" height="10" fill="{}"/><text x="{}" y="{}">{}</text>"#,
+            left,
+            CHART_MARGIN - 24,
+            color,
+            left + 14,
+            CHART_MARGIN - 15,
+            name
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn labels_chart(labels: &[LabelStats]) -> String {
+    // Stacked bars of the open and closed issues of the most used labels
+    if labels.is_empty() {
+        return String::from("<p>No labels.</p>");
+    }
+
+    let labels: &[LabelStats] = &labels[..labels.len().min(MAX_BARS)];
+    let maximum: usize = labels
+        .iter()
+        .map(|label| label.total())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let name_width: i64 = 180;
+    let bar_width: i64 = CHART_WIDTH - name_width - 80;
+    let height: i64 = labels.len() as i64 * BAR_HEIGHT;
+
+    let mut svg: String = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" role="img" aria-label="Open and closed issues per label">"#,
+        CHART_WIDTH, height, CHART_WIDTH, height
+    );
+
+    for (index, label) in labels.iter().enumerate() {
+        let top: i64 = index as i64 * BAR_HEIGHT;
+        let open: i64 = label.open as i64 * bar_width / maximum as i64;
+        let closed: i64 = label.closed as i64 * bar_width / maximum as i64;
+        let (color, _) = label_colors(label.color.as_deref());
+
+        svg.push_str(&format!(
+            r#"<circle cx="6" cy="{}" r="5" fill="{}"/><text x="16" y="{}">{}</text>"#,
+            top + BAR_HEIGHT / 2,
+            color,
+            top + BAR_HEIGHT / 2 + 4,
+            escape(&label.name)
+        ));
+        svg.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"><title>{} open</title></rect>"#,
+            name_width,
+            top + 3,
+            open,
+            BAR_HEIGHT - 6,
+            OPEN_COLOR,
+            label.open
+        ));
+        svg.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"><title>{} closed</title></rect>"#,
+            name_width + open,
+            top + 3,
+            closed,
+            BAR_HEIGHT - 6,
+            CLOSED_COLOR,
+            label.closed
+        ));
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}">{} open, {} closed</text>"#,
+            name_width + open + closed + 6,
+            top + BAR_HEIGHT / 2 + 4,
+            label.open,
+            label.closed
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn labels_summary(labels: &[LabelStats]) -> String {
+    // Chips of every label with their number of issues
+    labels
+        .iter()
+        .map(|label| {
+            format!(
+                "{} {}",
+                label_chip(&label.name, label.color.as_deref()),
+                label.total()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(" &nbsp; ")
+}
+
+pub fn html_report(issues: &[Issue], filters: &dyn Filter, settings: &ExportSettings) -> String {
+    // Single page report of the exported issues
+    let labels: Vec<LabelStats> = collect_repo_labels(issues, "usage");
+    let now: Date = settings.now.date_in(&settings.timezone);
+
+    let mut html =
+        String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str("<title>Issue report</title>\n");
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+
+    html.push_str("<h1>Issue report</h1>\n");
+    html.push_str(&format!(
+        "<p class=\"generated\">{} issues, as of {}.</p>\n",
+        issues.len(),
+        now
+    ));
+
+    html.push_str("<h2>Summary</h2>\n");
+    html.push_str(&state_cards(issues));
+    html.push_str(&format!("\n<p>{}</p>\n", labels_summary(&labels)));
+
+    html.push_str("<h2>Issues over time</h2>\n");
+    html.push_str(&timeline_chart(&timeline(issues, settings)));
+
+    html.push_str("\n<h2>Issues by label</h2>\n");
+    html.push_str(&labels_chart(&labels));
+
+    html.push_str("\n<h2>Issues</h2>\n");
+    html.push_str(&format!(
+        "<input id=\"filter\" type=\"search\" placeholder=\"Filter issues\"><p><span id=\"shown\">{}</span> of {} issues shown.</p>\n",
+        issues.len(),
+        issues.len()
+    ));
+    html.push_str(&issues_table(issues, filters, settings));
+
+    html.push_str(&format!(
+        "\n<script>{}</script>\n</body>\n</html>\n",
+        SCRIPT
+    ));
+
+    html
+}
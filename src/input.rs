@@ -1,5 +1,16 @@
+use crate::columns::Column;
+use crate::date::parse_age;
+use crate::date::Date;
 use crate::err::ErrKind;
 use crate::err::IssueParserErr;
+use crate::filters::NumberFilter;
+use crate::filters::ReactionsFilter;
+use crate::filters::StateFilter;
+use crate::glob;
+use crate::parser::DateField;
+use crate::parser::InputFormat;
+use crate::parser::IssueSort;
+use crate::timezone::TimeZone;
 use clap::Parser;
 use std::path::Path;
 
@@ -20,28 +31,156 @@ pub struct Args {
         long,
         short,
         default_value = "out.csv",
-        help = "Specify a file to store the csv."
+        help = "Specify a file to store the exported issues, or - to write them to the standard output."
     )]
     pub output: String,
 
+    /// --format=format
+    #[arg(
+        long,
+        default_value = "auto",
+        value_parser = ["auto", "csv", "json", "jsonl", "xlsx", "markdown", "html"],
+        help = "Format of the output file, detected from its extension by default (.json, .jsonl or .ndjson, .xlsx, .md or .markdown, .html or .htm, csv otherwise)."
+    )]
+    pub format: String,
+
+    /// --sheets=group
+    #[arg(
+        long,
+        value_parser = ["label", "state"],
+        help = "Add one sheet per label or per state to XLSX workbooks, after the sheet of all issues."
+    )]
+    pub sheets: Option<String>,
+
+    /// --changelog
+    #[arg(
+        long,
+        help = "Write the closed issues as a Markdown changelog grouped in sections by label. --start-date and --end-date apply to the closing date."
+    )]
+    pub changelog: bool,
+
+    /// --changelog-sections=file
+    #[arg(
+        long,
+        help = "JSON file mapping labels to the sections of the changelog, e.g. {\"sections\": [{\"title\": \"Bug fixes\", \"labels\": [\"bug\"]}]}."
+    )]
+    pub changelog_sections: Option<String>,
+
+    /// --columns=columns
+    #[arg(
+        long,
+        help = "Comma-separated columns to export, in order, with an optional header, e.g. 'id,title=Summary,state,labels'. Columns: id, title, state, state_reason, created, updated, closed, labels, assignees, author, milestone, repository, url, comments, reactions, age_days, time_to_close, business_days, business_hours, match."
+    )]
+    pub columns: Option<String>,
+
     /// --label=label
     #[arg(
         long,
         short,
         default_value = "(all)",
-        help = "Filter the issues based on a label."
+        help = "Filter the issues based on labels. Repeat the flag or combine labels with ',' (and), '|' (or) and '!' (not), e.g. 'bug,!wontfix'. Labels can be globs ('area:*') or regexes ('/^prio:(high|low)$/')."
+    )]
+    pub label: Vec<String>,
+
+    /// -i, --ignore-case
+    #[arg(
+        long,
+        short,
+        help = "Match labels without regard to case, including globs and regexes."
+    )]
+    pub ignore_case: bool,
+
+    /// --label-map=file
+    #[arg(
+        long,
+        help = "JSON file of rules renaming, merging or dropping labels after loading."
+    )]
+    pub label_map: Option<String>,
+
+    /// --search=text
+    #[arg(
+        long,
+        help = "Only consider issues whose title, body or comments contain this text, ignoring case."
+    )]
+    pub search: Option<String>,
+
+    /// --search-regex=regex
+    #[arg(
+        long,
+        help = "Only consider issues whose title, body or comments match this regex."
+    )]
+    pub search_regex: Option<String>,
+
+    /// --search-snippet
+    #[arg(
+        long,
+        help = "Export the text matched by --search or --search-regex in a 'Match' column."
+    )]
+    pub search_snippet: bool,
+
+    /// -q, --query=query
+    #[arg(
+        long,
+        short,
+        help = "GitHub-style search query, e.g. 'is:open label:bug -label:wontfix author:alice created:>2023-01-01 \"panic in\"'."
     )]
-    pub label: String,
+    pub query: Option<String>,
 
     /// --print-labels
     #[arg(long, help = "Print all available labels in the repository.")]
     pub print_labels: bool,
 
+    /// --list-format=format
+    #[arg(
+        long,
+        default_value = "text",
+        value_parser = ["text", "csv", "json"],
+        help = "Format of the labels printed with --print-labels."
+    )]
+    pub list_format: String,
+
+    /// --list-sort=key
+    #[arg(
+        long,
+        default_value = "name",
+        value_parser = ["name", "usage"],
+        help = "Sort the labels printed with --print-labels by name or by number of issues."
+    )]
+    pub list_sort: String,
+
+    /// --author=login
+    #[arg(
+        long,
+        help = "Only consider issues opened by this user. Repeat the flag to accept several users."
+    )]
+    pub author: Vec<String>,
+
+    /// --assignee=login
+    #[arg(
+        long,
+        help = "Only consider issues assigned to this user, or unassigned issues with 'none'. Repeat the flag to accept several users."
+    )]
+    pub assignee: Vec<String>,
+
+    /// --milestone=title
+    #[arg(
+        long,
+        help = "Only consider issues in this milestone, or without milestone with 'none'. Repeat the flag to accept several milestones."
+    )]
+    pub milestone: Vec<String>,
+
+    /// --number=range
+    #[arg(
+        long,
+        help = "Only consider issues whose number is in this range, e.g. 100..250, 100..*, >=100 or 42. Repeat the flag to accept several ranges."
+    )]
+    pub number: Vec<String>,
+
     /// --start-date=date
     #[arg(
         long,
         default_value = "(oldest)",
-        help = "Only consider issues updated after this date. Format: YYYY-MM-DD"
+        help = "Only consider issues updated after this date. Format: YYYY-MM-DD, YYYY-Www, today, yesterday, this-/last-month (week, quarter, year) or 30d (w, m, y), optionally prefixed with created:, updated: or closed:"
     )]
     pub start_date: String,
 
@@ -49,34 +188,170 @@ pub struct Args {
     #[arg(
         long,
         default_value = "(newest)",
-        help = "Only consider issues updated before this date. Format: YYYY-MM-DD"
+        help = "Only consider issues updated before this date. Same formats as --start-date"
     )]
     pub end_date: String,
 
+    /// --date-field=field
+    #[arg(
+        long,
+        default_value = "updated",
+        value_parser = ["created", "updated", "closed"],
+        help = "Timestamp compared to --start-date and --end-date. Issues that are not closed never match closed dates."
+    )]
+    pub date_field: String,
+
+    /// --min-comments=count
+    #[arg(
+        long,
+        help = "Only consider issues with at least this number of comments."
+    )]
+    pub min_comments: Option<u32>,
+
+    /// --min-reactions=[reaction:]count
+    #[arg(
+        long,
+        help = "Only consider issues with at least this number of reactions, or of a single reaction such as '+1:10' (+1, -1, laugh, hooray, confused, heart, rocket, eyes)."
+    )]
+    pub min_reactions: Option<String>,
+
+    /// --sort=key[:asc|desc]
+    #[arg(
+        long,
+        help = "Sort the exported issues by number, created, updated, comments, reactions or a single reaction such as +1, e.g. 'comments' or 'created:desc'. Counts are sorted from the highest by default."
+    )]
+    pub sort: Option<String>,
+
+    /// --stale=age
+    #[arg(
+        long,
+        help = "Only consider issues not updated for at least this age, e.g. 90d, 12w, 6m or 1y. Counted in working days with --business-time."
+    )]
+    pub stale: Option<String>,
+
+    /// --older-than=age
+    #[arg(
+        long,
+        help = "Only consider issues created more than this age ago, e.g. 30d. Same formats as --stale"
+    )]
+    pub older_than: Option<String>,
+
+    /// --younger-than=age
+    #[arg(
+        long,
+        help = "Only consider issues created less than this age ago, e.g. 7d. Same formats as --stale"
+    )]
+    pub younger_than: Option<String>,
+
+    /// --aging-report
+    #[arg(
+        long,
+        help = "Print the number of open issues per label and age band (0-7d, 8-30d, 31-90d, 90d+) instead of exporting them, in the format of --list-format."
+    )]
+    pub aging_report: bool,
+
+    /// --timezone=zone
+    #[arg(
+        long,
+        default_value = "UTC",
+        help = "Time zone used to filter and format dates: UTC, a fixed offset such as +02:00 or an IANA name such as Europe/Paris."
+    )]
+    pub timezone: String,
+
+    /// --business-time
+    #[arg(
+        long,
+        help = "Export the time issues have been open in working days and working hours, and count the ages of --stale, --older-than and --younger-than in working days."
+    )]
+    pub business_time: bool,
+
+    /// --weekend=days
+    #[arg(
+        long,
+        default_value = "sat,sun",
+        help = "Days excluded from working time, e.g. fri,sat. Use 'none' for no weekend."
+    )]
+    pub weekend: String,
+
+    /// --working-hours=hours
+    #[arg(
+        long,
+        default_value = "09:00-17:00",
+        help = "Working hours in local time. Format: HH:MM-HH:MM"
+    )]
+    pub working_hours: String,
+
+    /// --holidays=file
+    #[arg(
+        long,
+        help = "File of holidays excluded from working time: one YYYY-MM-DD date per line or an iCalendar (.ics) file."
+    )]
+    pub holidays: Option<String>,
+
+    /// --now=date
+    #[arg(
+        long,
+        default_value = "(today)",
+        help = "Reference date for relative date expressions and durations. Format: YYYY-MM-DD or YYYY-MM-DDTHH:MM:SSZ"
+    )]
+    pub now: String,
+
     /// --state=state
     #[arg(
         long,
         short,
         default_value = "(any)",
-        help = "Only consider issues that have a particular state."
+        help = "Only consider issues that have a particular state: open, closed, or a state with the reason of its last change (closed:completed, closed:not_planned, closed:duplicate, open:reopened)."
     )]
     pub state: String,
 
+    /// -v, --verbose
+    #[arg(long, short, help = "Print details about the parsed filters.")]
+    pub verbose: bool,
+
     /// -u, --url
     #[arg(long, short, help = "GET the JSON file from GitHub API.")]
     pub get: bool,
 
-    /// Positional argument
-    #[arg(help = "Required JSON file link (local or from GitHub API).")]
-    pub json: String,
+    /// --input-format=format
+    #[arg(
+        long,
+        default_value = "auto",
+        value_parser = ["auto", "json", "jsonl", "csv"],
+        help = "Format of the inputs, detected from the extension or the content by default."
+    )]
+    pub input_format: String,
+
+    /// Positional arguments
+    #[arg(
+        required = true,
+        help = "Required input files (JSON, JSON Lines or csv) or glob patterns, '-' for stdin (GitHub repositories with --get)."
+    )]
+    pub inputs: Vec<String>,
 }
 
-pub fn check_inputs(
-    filepath_str: &str,
-    filename: &str,
-    dates: [&str; 2],
-    get: &bool,
-) -> Result<(), IssueParserErr> {
+pub fn expand_inputs(inputs: &[String], get: &bool) -> Result<Vec<String>, IssueParserErr> {
+    // Expand glob patterns into the list of files they match
+    // Repository names are kept as is when fetching from GitHub
+    let mut expanded: Vec<String> = Vec::new();
+
+    for input in inputs {
+        if !get && glob::is_pattern(input) {
+            expanded.append(&mut glob::expand(input)?);
+        } else {
+            expanded.push(String::from(input));
+        }
+    }
+
+    Ok(expanded)
+}
+
+fn check_input(filepath_str: &str, format: &InputFormat, get: &bool) -> Result<(), IssueParserErr> {
+    // The standard input is always accepted when reading local inputs
+    if !get && filepath_str == "-" {
+        return Ok(());
+    }
+
     if !get {
         // Convert the json file to a Path object
         let filepath = Path::new(filepath_str);
@@ -90,8 +365,12 @@ pub fn check_inputs(
             return Err(e);
         }
 
-        // Check if the specified path is a JSON file
-        if filepath.extension().and_then(|ext| ext.to_str()) != Some("json") {
+        // Check if the specified path is a supported file, unless the format is forced
+        let extension = filepath.extension().and_then(|ext| ext.to_str());
+
+        if *format == InputFormat::Auto
+            && !matches!(extension, Some("json" | "jsonl" | "ndjson" | "csv"))
+        {
             let e = IssueParserErr {
                 msg: format!("'{}' is not a json file!", filepath.display()),
                 kind: ErrKind::Input,
@@ -106,53 +385,95 @@ pub fn check_inputs(
         return Err(e);
     }
 
-    // Check if the output filename contains rejectable characters
-    for part in filename.split('.') {
-        if !part.chars().all(char::is_alphanumeric) {
-            let e = IssueParserErr {
-                msg: format!("{}: filename contains special characters.", &filename),
-                kind: ErrKind::Input,
-            };
-            return Err(e);
-        }
-    }
+    Ok(())
+}
 
-    let mut is_default: bool;
-    let mut date_num: Vec<&str>;
+pub fn check_inputs(
+    inputs: &[String],
+    format: &InputFormat,
+    args: &Args,
+) -> Result<(), IssueParserErr> {
+    for input in inputs {
+        check_input(input, format, &args.get)?;
+    }
 
-    for date in dates {
-        is_default = date == "(oldest)" || date == "(newest)";
+    // The standard input can only be read once
+    if !args.get && inputs.iter().filter(|input| *input == "-").count() > 1 {
+        return Err(IssueParserErr {
+            msg: String::from("'-': the standard input can only be given once."),
+            kind: ErrKind::Input,
+        });
+    }
 
-        if !is_default {
-            date_num = date.split('-').collect();
+    let filename: &str = &args.output;
 
-            if date_num.len() != 3 {
+    // Check if the output filename contains rejectable characters, '-' being the standard output
+    if filename != "-" {
+        for part in filename.split('.') {
+            if !part.chars().all(char::is_alphanumeric) {
                 let e = IssueParserErr {
-                    msg: format!("{}: date is not at the right format (YYYY-MM-DD).", &date),
+                    msg: format!("{}: filename contains special characters.", &filename),
                     kind: ErrKind::Input,
                 };
                 return Err(e);
             }
+        }
+    }
 
-            for num in &date_num {
-                if !num.chars().all(char::is_numeric) {
-                    let e = IssueParserErr {
-                        msg: format!("{}: date contains non-numbers characters.", &date),
-                        kind: ErrKind::Input,
-                    };
-                    return Err(e);
-                }
-            }
+    // Legacy Excel workbooks cannot be written, only XLSX ones
+    if Path::new(filename)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xls"))
+    {
+        return Err(IssueParserErr {
+            msg: format!(
+                "{}: .xls workbooks are not supported, use .xlsx instead.",
+                &filename
+            ),
+            kind: ErrKind::Input,
+        });
+    }
 
-            if (date_num[0].len() != 4) || (date_num[1].len() != 2) || (date_num[2].len() != 2) {
-                let e = IssueParserErr {
-                    msg: format!("{}: date is not at the right format (YYYY-MM-DD).", &date),
-                    kind: ErrKind::Input,
-                };
-                return Err(e);
-            }
+    // Check if the state is known, suggesting the closest value otherwise
+    if args.state != "(any)" {
+        StateFilter::parse(&args.state)?;
+    }
+
+    // Check if the issue number ranges are valid
+    NumberFilter::parse(&args.number)?;
+
+    // Check if the reaction threshold and the sort key are known
+    if let Some(min) = &args.min_reactions {
+        ReactionsFilter::parse(min)?;
+    }
+
+    if let Some(sort) = &args.sort {
+        IssueSort::parse(sort)?;
+    }
+
+    // Check if the exported columns are known
+    if let Some(columns) = &args.columns {
+        Column::parse_list(columns)?;
+    }
+
+    // Check if the dates exist in the calendar or are valid expressions
+    let timezone: TimeZone = TimeZone::parse(&args.timezone)?;
+    let now: Date = Date::reference(&args.now, &timezone)?;
+
+    for date in [&args.start_date, &args.end_date] {
+        if date != "(oldest)" && date != "(newest)" {
+            let (_, expr) = DateField::split(date, DateField::Updated)?;
+            Date::resolve(expr, &now)?;
         }
     }
 
+    // Check if the ages are valid
+    for age in [&args.stale, &args.older_than, &args.younger_than]
+        .into_iter()
+        .flatten()
+    {
+        parse_age(age, &now)?;
+    }
+
     Ok(())
 }
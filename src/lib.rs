@@ -1,9 +1,19 @@
 // issue-tracking crate library that allows us to use functions and structures throughout the files
 
+pub mod columns;
 pub mod date;
 pub mod err;
 pub mod filters;
 pub mod get;
+pub mod glob;
+pub mod html;
 pub mod input;
+pub mod markdown;
 pub mod parser;
+pub mod query;
+pub mod reader;
+pub mod report;
+pub mod timezone;
 pub mod writer;
+pub mod xlsx;
+pub mod zip;
@@ -1,80 +1,233 @@
 use clap::Parser;
-use std::path::Path;
 
+use issue_tracking::date::Date;
+use issue_tracking::date::WorkCalendar;
 use issue_tracking::filters::Filters;
+use issue_tracking::filters::LabelFilter;
 use issue_tracking::get::request_json;
 use issue_tracking::input::check_inputs;
+use issue_tracking::input::expand_inputs;
 use issue_tracking::input::Args;
-use issue_tracking::parser::parse_json_input;
+use issue_tracking::parser::merge_repositories;
+use issue_tracking::parser::parse_input;
 use issue_tracking::parser::print_repo_labels;
+use issue_tracking::parser::InputFormat;
+use issue_tracking::parser::LabelMap;
 use issue_tracking::parser::Repository;
+use issue_tracking::report::aging_report;
+use issue_tracking::report::print_aging_report;
+use issue_tracking::timezone::TimeZone;
 use issue_tracking::writer::build_output_filename;
-use issue_tracking::writer::write_csv;
+use issue_tracking::writer::write_output;
+use issue_tracking::writer::ExportSettings;
+use issue_tracking::writer::OutputFormat;
 /*
     Main thread of the application.
     Arguments are processed here and external functions are called to build the output.
 */
 
+fn status(message: &str, piped: bool) {
+    // Keep the standard output clean for the exported data
+    if piped {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+}
+
 fn main() {
     // Retrieve arguments
     let args = Args::parse();
 
-    // Convert the json file to a Path object
-    let json_file = Path::new(&args.json);
+    // Expand glob patterns into the list of inputs, return error if one does not match
+    let inputs: Vec<String> = match expand_inputs(&args.inputs, &args.get) {
+        Ok(inputs) => inputs,
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    };
 
     // Check if the inputs are correct, return error if not
-    if let Err(error) = check_inputs(
-        &args.json,
-        &args.output,
-        [&args.start_date, &args.end_date],
-        &args.get,
-    ) {
+    let input_format: InputFormat = InputFormat::from_arg(&args.input_format);
+
+    if let Err(error) = check_inputs(&inputs, &input_format, &args) {
         eprintln!("{}", error);
         return;
     };
 
-    // Parse the JSON file and store its data into a Repository structure
+    // Parse every input and store its data into a Repository structure
     // Returns if an error occured
-    let repository_issues: Repository = if !args.get {
-        match parse_json_input(json_file) {
-            Ok(repository) => repository,
-            Err(error) => {
-                eprintln!("{}", error);
-                return;
+    let mut repositories: Vec<Repository> = Vec::new();
+
+    for input in &inputs {
+        let repository: Repository = if !args.get {
+            match parse_input(input, &input_format) {
+                Ok(repository) => repository,
+                Err(error) => {
+                    eprintln!("{}", error);
+                    return;
+                }
             }
-        }
-    } else {
-        match request_json(&args.json) {
-            Ok(repository) => repository,
+        } else {
+            match request_json(input) {
+                Ok(repository) => repository,
+                Err(error) => {
+                    eprintln!("{}", error);
+                    return;
+                }
+            }
+        };
+
+        repositories.push(repository);
+    }
+
+    // Merge all inputs into a single repository, keeping the latest copy of duplicated issues
+    let (mut repository_issues, duplicates) = merge_repositories(repositories);
+    let sources: String = inputs.join(", ");
+
+    // The summary is not displayed when printing machine-readable labels
+    // Status messages go to the standard error when the export is written to the standard output
+    let listing: bool = args.print_labels || args.aging_report;
+    let piped: bool = !listing && args.output == "-";
+
+    if inputs.len() > 1 && (!listing || args.list_format == "text") {
+        status(
+            &format!(
+                "Merged {} duplicate issues from {} inputs.",
+                duplicates,
+                inputs.len()
+            ),
+            piped,
+        );
+    }
+
+    // Normalize the labels with the mapping rules, if any
+    // Returns if an error occured
+    if let Some(label_map) = &args.label_map {
+        match LabelMap::from_file(label_map) {
+            Ok(map) => map.apply(&mut repository_issues),
             Err(error) => {
                 eprintln!("{}", error);
                 return;
             }
         }
-    };
+    }
 
     // Parse the issues and display used labels
     // Returns after execution
     if args.print_labels {
-        println!("Available labels from {}:", &args.json);
-        print_repo_labels(repository_issues.issues);
+        let label_filter: LabelFilter = match LabelFilter::new(&args.label, args.ignore_case) {
+            Ok(label_filter) => label_filter,
+            Err(error) => {
+                eprintln!("{}", error);
+                return;
+            }
+        };
+
+        if let Err(error) = print_repo_labels(
+            &repository_issues.issues,
+            &sources,
+            &args.list_format,
+            &args.list_sort,
+            &label_filter,
+        ) {
+            eprintln!("{}", error);
+        }
         return;
     };
 
     // Build the output file path into which we'll write data
     // Returns if an error occured
-    let filename: String = build_output_filename(String::from(&args.output));
+    // Changelogs are always written in Markdown
+    let output_format: OutputFormat = if args.changelog {
+        OutputFormat::Markdown
+    } else {
+        OutputFormat::from_arg(&args.format, &args.output)
+    };
+    let filename: String = build_output_filename(String::from(&args.output), &output_format);
 
-    // Write the csv output file from the repository structure, filename and potentially filters
+    // Write the output file from the repository structure, filename and potentially filters
     // Returns if an error occured
-    match write_csv(
+    let filters: Filters = match Filters::from_args(&args) {
+        Ok(filters) => filters,
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    };
+
+    // Show how the filters were understood
+    if args.verbose {
+        for filter in &filters.filters {
+            eprintln!("Filter: {}", filter.describe());
+        }
+    }
+
+    // Print the aging report of the filtered open issues
+    // Returns after execution
+    if args.aging_report {
+        let timezone: TimeZone = match TimeZone::parse(&args.timezone) {
+            Ok(timezone) => timezone,
+            Err(error) => {
+                eprintln!("{}", error);
+                return;
+            }
+        };
+        let now: Date = match Date::reference(&args.now, &timezone) {
+            Ok(now) => now,
+            Err(error) => {
+                eprintln!("{}", error);
+                return;
+            }
+        };
+
+        // Ages are counted in working days with --business-time, like the age filters
+        let calendar: Option<WorkCalendar> = if args.business_time {
+            match WorkCalendar::new(&args.weekend, &args.working_hours, args.holidays.as_deref()) {
+                Ok(calendar) => Some(calendar),
+                Err(error) => {
+                    eprintln!("{}", error);
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+
+        let report = aging_report(
+            &repository_issues.issues,
+            &filters,
+            &now,
+            &timezone,
+            calendar.as_ref(),
+        );
+
+        if let Err(error) = print_aging_report(&report, &sources, &args.list_format) {
+            eprintln!("{}", error);
+        }
+        return;
+    }
+
+    let settings: ExportSettings = match ExportSettings::from_args(&args) {
+        Ok(settings) => settings,
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    };
+
+    match write_output(
         repository_issues.issues,
         &filename,
-        Filters::from_args(&args),
+        &output_format,
+        &filters,
+        &settings,
     ) {
         Ok(()) => {
             // Display created file path
-            println!("Built {} from {}.", &filename, &args.json);
+            let target: &str = if piped { "(stdout)" } else { &filename };
+            status(&format!("Built {} from {}.", target, &sources), piped);
         }
         Err(error) => {
             eprintln!("{}", error);
@@ -0,0 +1,210 @@
+use crate::columns::Field;
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+use crate::filters::Filter;
+use crate::parser::read_input;
+use crate::parser::Issue;
+use crate::parser::IssueState;
+use crate::parser::LabelPattern;
+use crate::parser::StateReason;
+use crate::writer::ExportSettings;
+use serde_derive::Deserialize;
+
+/*
+    Markdown outputs, to paste issues into release notes and wiki pages.
+    Issues are written as a table of the exported columns, or as a changelog of the closed issues grouped by label.
+*/
+
+const OTHER_SECTION: &str = "Other changes";
+
+pub fn escape(text: &str) -> String {
+    // Pipes would end the table cell and line breaks the row
+    text.replace('|', "\\|")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn issue_link(issue: &Issue) -> String {
+    // Linked issue number, e.g. [#12](https://github.com/owner/repository/issues/12)
+    // Only http(s) URLs are linked
+    match issue.link() {
+        Some(url) => format!("[#{}]({})", issue.number, url),
+        None => format!("#{}", issue.number),
+    }
+}
+
+pub fn markdown_table(issues: &[Issue], filters: &dyn Filter, settings: &ExportSettings) -> String {
+    // Table of the exported columns, issue numbers link to their page
+    let headers: Vec<String> = settings
+        .columns
+        .iter()
+        .map(|column| escape(&column.header))
+        .collect();
+
+    let mut table: String = format!("| {} |\n", headers.join(" | "));
+    table.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+
+    for issue in issues {
+        let cells: Vec<String> = settings
+            .columns
+            .iter()
+            .map(|column| match column.field {
+                Field::Id => issue_link(issue),
+                _ => escape(&column.value(issue, settings, filters).to_string()),
+            })
+            .collect();
+
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+
+    table
+}
+
+// Changelog section as written in the sections file
+#[derive(Deserialize, Debug)]
+struct SectionDef {
+    title: String,
+    labels: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChangelogDef {
+    sections: Vec<SectionDef>,
+    other: Option<String>,
+}
+
+// Section of a changelog, with the label patterns of its issues
+pub struct Section {
+    pub title: String,
+    pub labels: Vec<LabelPattern>,
+}
+
+// Mapping of the labels to the sections of a changelog
+// Issues are listed in the first section matching one of their labels, or in the last section
+pub struct Changelog {
+    pub sections: Vec<Section>,
+    pub other: String,
+}
+
+impl Default for Changelog {
+    fn default() -> Changelog {
+        let section = |title: &str, labels: &[&str]| Section {
+            title: String::from(title),
+            labels: labels
+                .iter()
+                .map(|label| LabelPattern::Exact {
+                    name: String::from(*label),
+                    ignore_case: true,
+                })
+                .collect(),
+        };
+
+        Changelog {
+            sections: vec![
+                section("Features", &["feature", "enhancement"]),
+                section("Bug fixes", &["bug", "fix"]),
+                section("Documentation", &["documentation", "docs"]),
+            ],
+            other: String::from(OTHER_SECTION),
+        }
+    }
+}
+
+impl Changelog {
+    pub fn from_json(text: &str, name: &str) -> Result<Changelog, IssueParserErr> {
+        // Build the sections from the content of a sections file, labels can be globs or regexes
+        let definition: ChangelogDef = match serde_json::from_str(text) {
+            Ok(definition) => definition,
+            Err(error) => {
+                return Err(IssueParserErr {
+                    msg: format!("'{}': {}", name, error),
+                    kind: ErrKind::Input,
+                });
+            }
+        };
+
+        let mut sections: Vec<Section> = Vec::new();
+
+        for section in definition.sections {
+            let mut labels: Vec<LabelPattern> = Vec::new();
+
+            for label in &section.labels {
+                labels.push(LabelPattern::parse(label, true)?);
+            }
+
+            sections.push(Section {
+                title: section.title,
+                labels,
+            });
+        }
+
+        Ok(Changelog {
+            sections,
+            other: definition.other.unwrap_or(String::from(OTHER_SECTION)),
+        })
+    }
+
+    pub fn from_file(path: &str) -> Result<Changelog, IssueParserErr> {
+        Changelog::from_json(&read_input(path)?, path)
+    }
+
+    pub fn section(&self, issue: &Issue) -> &str {
+        // Title of the section an issue is listed in
+        self.sections
+            .iter()
+            .find(|section| section.labels.iter().any(|label| issue.is_labeled(label)))
+            .map_or(self.other.as_str(), |section| section.title.as_str())
+    }
+
+    pub fn is_listed(issue: &Issue) -> bool {
+        // Only the closed issues that were done are listed, not the ones closed as not planned or duplicated
+        issue.state == IssueState::Closed
+            && !matches!(
+                issue.state_reason,
+                Some(StateReason::NotPlanned) | Some(StateReason::Duplicate)
+            )
+    }
+
+    pub fn render(&self, issues: &[Issue]) -> String {
+        // Headed bullet lists, in the order of the sections, empty sections are skipped
+        let listed: Vec<&Issue> = issues
+            .iter()
+            .filter(|issue| Changelog::is_listed(issue))
+            .collect();
+
+        let mut titles: Vec<&str> = self
+            .sections
+            .iter()
+            .map(|section| section.title.as_str())
+            .collect();
+        titles.push(&self.other);
+
+        let mut changelog = String::new();
+
+        for (index, title) in titles.iter().enumerate() {
+            // Sections with the same title are merged into the first one
+            if titles[..index].contains(title) {
+                continue;
+            }
+
+            let entries: Vec<String> = listed
+                .iter()
+                .filter(|issue| self.section(issue) == *title)
+                .map(|issue| format!("- {} ({})", escape(&issue.title), issue_link(issue)))
+                .collect();
+
+            if entries.is_empty() {
+                continue;
+            }
+
+            if !changelog.is_empty() {
+                changelog.push('\n');
+            }
+
+            changelog.push_str(&format!("## {}\n\n{}\n", title, entries.join("\n")));
+        }
+
+        changelog
+    }
+}
@@ -1,6 +1,16 @@
+use crate::date::Timestamp;
+use crate::err::did_you_mean;
 use crate::err::ErrKind;
 use crate::err::IssueParserErr;
+use crate::filters::LabelFilter;
+use crate::glob;
+use crate::reader::parse_csv;
+use regex::Regex;
+use regex::RegexBuilder;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 use std::path::Path;
 
 /*
@@ -14,28 +24,451 @@ pub struct Repository {
     pub issues: Vec<Issue>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Issue {
+    pub repository_url: Option<String>,
+    pub html_url: Option<String>,
     pub title: String,
     pub number: i32,
     pub labels: Vec<Label>,
-    pub state: String,
-    pub created_at: String,
-    pub updated_at: String,
-    pub closed_at: Option<String>,
+    pub state: IssueState,
+    #[serde(
+        default,
+        alias = "stateReason",
+        deserialize_with = "deserialize_state_reason"
+    )]
+    pub state_reason: Option<StateReason>,
+    // The GitHub CLI names the timestamps in camel case
+    #[serde(alias = "createdAt")]
+    pub created_at: Timestamp,
+    #[serde(alias = "updatedAt")]
+    pub updated_at: Timestamp,
+    #[serde(alias = "closedAt")]
+    pub closed_at: Option<Timestamp>,
     pub draft: Option<bool>,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub comments: Comments,
+    #[serde(default)]
+    pub reactions: Reactions,
+    // The GitHub CLI names the creator of the issue its author
+    #[serde(alias = "author")]
+    pub user: Option<User>,
+    #[serde(default)]
+    pub assignees: Vec<User>,
+    pub milestone: Option<Milestone>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct User {
+    pub login: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Milestone {
+    pub title: String,
+}
+
+// State of an issue
+// The GitHub API writes it in lower case, the GitHub CLI in upper case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum IssueState {
+    #[default]
+    Open,
+    Closed,
+}
+
+impl IssueState {
+    pub fn parse(text: &str) -> Result<IssueState, IssueParserErr> {
+        match text.to_lowercase().as_str() {
+            "open" => Ok(IssueState::Open),
+            "closed" => Ok(IssueState::Closed),
+            _ => Err(IssueParserErr {
+                msg: format!("'{}': unknown issue state, expected open or closed.", text),
+                kind: ErrKind::Parser,
+            }),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueState::Open => "open",
+            IssueState::Closed => "closed",
+        }
+    }
+}
+
+impl fmt::Display for IssueState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for IssueState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for IssueState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<IssueState, D::Error> {
+        let text: String = String::deserialize(deserializer)?;
+        IssueState::parse(&text).map_err(|error| serde::de::Error::custom(error.msg))
+    }
+}
+
+// Reason of the last state change of an issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateReason {
+    Completed,
+    NotPlanned,
+    Duplicate,
+    Reopened,
+}
+
+impl StateReason {
+    pub fn parse(text: &str) -> Result<StateReason, IssueParserErr> {
+        // Spaces are accepted as in the GitHub interface, e.g. 'not planned'
+        match text.to_lowercase().replace(' ', "_").as_str() {
+            "completed" => Ok(StateReason::Completed),
+            "not_planned" => Ok(StateReason::NotPlanned),
+            "duplicate" => Ok(StateReason::Duplicate),
+            "reopened" => Ok(StateReason::Reopened),
+            _ => Err(IssueParserErr {
+                msg: format!(
+                    "'{}': unknown state reason, expected completed, not_planned, duplicate or reopened.",
+                    text
+                ),
+                kind: ErrKind::Parser,
+            }),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StateReason::Completed => "completed",
+            StateReason::NotPlanned => "not_planned",
+            StateReason::Duplicate => "duplicate",
+            StateReason::Reopened => "reopened",
+        }
+    }
+
+    pub fn state(&self) -> IssueState {
+        // State that an issue has after a change for this reason
+        match self {
+            StateReason::Reopened => IssueState::Open,
+            _ => IssueState::Closed,
+        }
+    }
+}
+
+impl fmt::Display for StateReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for StateReason {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+fn deserialize_state_reason<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<StateReason>, D::Error> {
+    // The GitHub CLI writes an empty reason for issues that never changed state
+    match Option::<String>::deserialize(deserializer)? {
+        Some(text) if !text.is_empty() => StateReason::parse(&text)
+            .map(Some)
+            .map_err(|error| serde::de::Error::custom(error.msg)),
+        _ => Ok(None),
+    }
+}
+
+// Comments of an issue
+// The GitHub API only provides their number, the GitHub CLI their content
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Comments {
+    Count(u32),
+    Thread(Vec<Comment>),
+}
+
+impl Default for Comments {
+    fn default() -> Comments {
+        Comments::Count(0)
+    }
+}
+
+impl Comments {
+    pub fn count(&self) -> u32 {
+        match self {
+            Comments::Count(count) => *count,
+            Comments::Thread(comments) => comments.len() as u32,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Comment {
+    #[serde(default)]
+    pub body: String,
+}
+
+// Summary of the reactions to an issue, only provided by the GitHub API
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Reactions {
+    pub total_count: u32,
+    #[serde(rename = "+1")]
+    pub plus_one: u32,
+    #[serde(rename = "-1")]
+    pub minus_one: u32,
+    pub laugh: u32,
+    pub hooray: u32,
+    pub confused: u32,
+    pub heart: u32,
+    pub rocket: u32,
+    pub eyes: u32,
+}
+
+// Kinds of reactions, as named by the GitHub API
+pub const REACTIONS: [&str; 8] = [
+    "+1", "-1", "laugh", "hooray", "confused", "heart", "rocket", "eyes",
+];
+
+impl Reactions {
+    pub fn get(&self, reaction: &str) -> Option<u32> {
+        // Number of reactions of a kind, e.g. '+1' or 'heart'
+        match reaction {
+            "+1" => Some(self.plus_one),
+            "-1" => Some(self.minus_one),
+            "laugh" => Some(self.laugh),
+            "hooray" => Some(self.hooray),
+            "confused" => Some(self.confused),
+            "heart" => Some(self.heart),
+            "rocket" => Some(self.rocket),
+            "eyes" => Some(self.eyes),
+            _ => None,
+        }
+    }
+
+    pub fn check(reaction: &str) -> Result<(), IssueParserErr> {
+        // Returns an error suggesting the closest kind if the reaction is unknown
+        if REACTIONS.contains(&reaction) {
+            return Ok(());
+        }
+
+        Err(IssueParserErr {
+            msg: format!(
+                "'{}': unknown reaction, expected one of {}.{}",
+                reaction,
+                REACTIONS.join(", "),
+                did_you_mean(reaction, &REACTIONS)
+            ),
+            kind: ErrKind::Input,
+        })
+    }
+}
+
+// Order of the exported issues, on a key and in a direction, e.g. 'comments' or 'created:asc'
+// Counts are sorted from the highest by default, other keys from the lowest
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueSort {
+    pub key: String,
+    pub descending: bool,
+}
+
+// Keys accepted by --sort, besides the kinds of reactions
+pub const SORT_KEYS: [&str; 5] = ["number", "created", "updated", "comments", "reactions"];
+
+impl IssueSort {
+    pub fn parse(text: &str) -> Result<IssueSort, IssueParserErr> {
+        // The direction is an optional suffix, e.g. '+1:desc'
+        let (key, direction) = match text.rsplit_once(':') {
+            Some((key, direction)) => (key, Some(direction)),
+            None => (text, None),
+        };
+
+        if !SORT_KEYS.contains(&key) && !REACTIONS.contains(&key) {
+            let keys: Vec<&str> = SORT_KEYS.iter().chain(REACTIONS.iter()).copied().collect();
+
+            return Err(IssueParserErr {
+                msg: format!(
+                    "'{}': unknown sort key, expected one of {}.{}",
+                    key,
+                    keys.join(", "),
+                    did_you_mean(key, &keys)
+                ),
+                kind: ErrKind::Input,
+            });
+        }
+
+        let descending: bool = match direction {
+            Some("asc") => false,
+            Some("desc") => true,
+            None => !matches!(key, "number" | "created" | "updated"),
+            Some(direction) => {
+                return Err(IssueParserErr {
+                    msg: format!(
+                        "'{}': unknown sort direction '{}', expected asc or desc.",
+                        text, direction
+                    ),
+                    kind: ErrKind::Input,
+                })
+            }
+        };
+
+        Ok(IssueSort {
+            key: String::from(key),
+            descending,
+        })
+    }
+
+    fn value(&self, issue: &Issue) -> i64 {
+        match self.key.as_str() {
+            "number" => issue.number as i64,
+            "created" => issue.created_at.seconds(),
+            "updated" => issue.updated_at.seconds(),
+            "comments" => issue.comments.count() as i64,
+            "reactions" => issue.reactions.total_count as i64,
+            reaction => issue.reactions.get(reaction).unwrap_or(0) as i64,
+        }
+    }
+
+    pub fn sort(&self, issues: &mut [Issue]) {
+        // The sort is stable, issues with equal values keep their order
+        if self.descending {
+            issues.sort_by_key(|issue| std::cmp::Reverse(self.value(issue)));
+        } else {
+            issues.sort_by_key(|issue| self.value(issue));
+        }
+    }
+}
+
+// Timestamps of an issue that dates can be compared to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateField {
+    Created,
+    Updated,
+    Closed,
+}
+
+impl DateField {
+    pub fn from_arg(arg: &str) -> Result<DateField, IssueParserErr> {
+        match arg {
+            "created" => Ok(DateField::Created),
+            "updated" => Ok(DateField::Updated),
+            "closed" => Ok(DateField::Closed),
+            _ => Err(IssueParserErr {
+                msg: format!(
+                    "'{}': unknown date field, expected created, updated or closed.",
+                    arg
+                ),
+                kind: ErrKind::Input,
+            }),
+        }
+    }
+
+    pub fn split(value: &str, default: DateField) -> Result<(DateField, &str), IssueParserErr> {
+        // Split an optional 'field:' prefix from a date, e.g. 'closed:2023-06-01'
+        match value.split_once(':') {
+            Some((field, date)) => Ok((DateField::from_arg(field)?, date)),
+            None => Ok((default, value)),
+        }
+    }
+}
+
+impl fmt::Display for DateField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DateField::Created => write!(f, "created"),
+            DateField::Updated => write!(f, "updated"),
+            DateField::Closed => write!(f, "closed"),
+        }
+    }
 }
 
 impl Issue {
-    pub fn format_date(date: String) -> String {
-        // Slice the orifinal date to only keep YYYY-MM-DD
-        date[..10].to_string()
+    pub fn url(&self) -> Option<String> {
+        // Web page of the issue, built from the repository API URL if missing
+        if let Some(html_url) = &self.html_url {
+            return Some(String::from(html_url));
+        }
+
+        self.repository_url.as_ref().map(|repository_url| {
+            format!(
+                "{}/issues/{}",
+                repository_url.replace("https://api.github.com/repos/", "https://github.com/"),
+                self.number
+            )
+        })
     }
 
-    pub fn is_labeled(&self, pattern: &str) -> bool {
-        // Detect if an issue contains a specified label
+    pub fn link(&self) -> Option<String> {
+        // Web page of the issue if it can be linked from documents, only http(s) URLs are kept
+        self.url()
+            .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+    }
+
+    pub fn repository(&self) -> Option<String> {
+        // Owner and name of the repository, e.g. 'owner/repository', from the repository API URL
+        // (https://api.github.com/repos/owner/repository) or the web page of the issue
+        // (https://github.com/owner/repository/issues/12)
+        if let Some(repository_url) = &self.repository_url {
+            let parts: Vec<&str> = repository_url
+                .split('/')
+                .filter(|part| !part.is_empty())
+                .collect();
+
+            if let [.., owner, name] = parts[..] {
+                return Some(format!("{}/{}", owner, name));
+            }
+        }
+
+        let html_url: &str = self.html_url.as_deref()?;
+        let path: &str = html_url
+            .split_once("://")
+            .map_or(html_url, |(_, path)| path);
+        let mut parts = path.split('/').skip(1);
+
+        match (parts.next(), parts.next()) {
+            (Some(owner), Some(name)) if !owner.is_empty() && !name.is_empty() => {
+                Some(format!("{}/{}", owner, name))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn texts(&self) -> Vec<&str> {
+        // Searchable text of the issue: title, then body and comments when they were parsed
+        let mut texts: Vec<&str> = vec![&self.title];
+
+        if let Some(body) = &self.body {
+            texts.push(body);
+        }
+
+        if let Comments::Thread(comments) = &self.comments {
+            texts.extend(comments.iter().map(|comment| comment.body.as_str()));
+        }
+
+        texts
+    }
+
+    pub fn timestamp(&self, field: DateField) -> Option<&Timestamp> {
+        // Timestamp of the issue for a date field, open issues have no closing date
+        match field {
+            DateField::Created => Some(&self.created_at),
+            DateField::Updated => Some(&self.updated_at),
+            DateField::Closed => self.closed_at.as_ref(),
+        }
+    }
+
+    pub fn is_labeled(&self, pattern: &LabelPattern) -> bool {
+        // Detect if an issue contains a label matching the pattern
         for label in &self.labels {
-            if label.name == pattern {
+            if pattern.matches(&label.name) {
                 return true;
             }
         }
@@ -44,22 +477,196 @@ impl Issue {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Label {
     pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+// Pattern matched against label names
+// Labels are compared exactly, with glob wildcards such as 'area:*' or with a regex written '/.../'
+#[derive(Debug, Clone)]
+pub enum LabelPattern {
+    Exact { name: String, ignore_case: bool },
+    Glob { pattern: String, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl LabelPattern {
+    pub fn parse(text: &str, ignore_case: bool) -> Result<LabelPattern, IssueParserErr> {
+        if let Some(inner) = text.strip_prefix('/') {
+            // A regex must be closed by a second slash
+            let source = match inner.strip_suffix('/') {
+                Some(source) => source,
+                None => {
+                    return Err(IssueParserErr {
+                        msg: format!("'{}': unterminated label regex.", text),
+                        kind: ErrKind::Input,
+                    })
+                }
+            };
+
+            return match RegexBuilder::new(source)
+                .case_insensitive(ignore_case)
+                .build()
+            {
+                Ok(regex) => Ok(LabelPattern::Regex(regex)),
+                Err(error) => Err(IssueParserErr {
+                    msg: format!("'{}': invalid label regex: {}", text, error),
+                    kind: ErrKind::Input,
+                }),
+            };
+        }
+
+        if glob::is_pattern(text) {
+            return Ok(LabelPattern::Glob {
+                pattern: String::from(text),
+                ignore_case,
+            });
+        }
+
+        Ok(LabelPattern::Exact {
+            name: String::from(text),
+            ignore_case,
+        })
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        // Returns true if the label name is matched by the pattern
+        match self {
+            LabelPattern::Exact {
+                name: expected,
+                ignore_case: false,
+            } => name == expected,
+            LabelPattern::Exact {
+                name: expected,
+                ignore_case: true,
+            } => name.to_lowercase() == expected.to_lowercase(),
+            LabelPattern::Glob {
+                pattern,
+                ignore_case: false,
+            } => glob::matches(pattern, name),
+            LabelPattern::Glob {
+                pattern,
+                ignore_case: true,
+            } => glob::matches(&pattern.to_lowercase(), &name.to_lowercase()),
+            LabelPattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl PartialEq for LabelPattern {
+    fn eq(&self, other: &LabelPattern) -> bool {
+        // Regexes are compared on their source
+        match (self, other) {
+            (
+                LabelPattern::Exact {
+                    name: a,
+                    ignore_case: x,
+                },
+                LabelPattern::Exact {
+                    name: b,
+                    ignore_case: y,
+                },
+            ) => a == b && x == y,
+            (
+                LabelPattern::Glob {
+                    pattern: a,
+                    ignore_case: x,
+                },
+                LabelPattern::Glob {
+                    pattern: b,
+                    ignore_case: y,
+                },
+            ) => a == b && x == y,
+            (LabelPattern::Regex(a), LabelPattern::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for LabelPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LabelPattern::Exact { name, .. } => write!(f, "'{}'", name),
+            LabelPattern::Glob { pattern, .. } => write!(f, "'{}'", pattern),
+            LabelPattern::Regex(regex) => write!(f, "/{}/", regex.as_str()),
+        }
+    }
+}
+
+// Usage of a label across the issues of a repository
+#[derive(Serialize, Debug)]
+pub struct LabelStats {
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub open: usize,
+    pub closed: usize,
+}
+
+impl LabelStats {
+    pub fn total(&self) -> usize {
+        self.open + self.closed
+    }
+}
+
+// Supported input formats, either forced by the user or detected from the input
+#[derive(Debug, PartialEq)]
+pub enum InputFormat {
+    Auto,
+    Json,
+    JsonLines,
+    Csv,
+}
+
+impl InputFormat {
+    pub fn from_arg(arg: &str) -> InputFormat {
+        // Build the input format from the --input-format argument
+        match arg {
+            "json" => InputFormat::Json,
+            "jsonl" => InputFormat::JsonLines,
+            "csv" => InputFormat::Csv,
+            _ => InputFormat::Auto,
+        }
+    }
+
+    fn detect(input: &str, text: &str) -> InputFormat {
+        // Detect the format from the file extension, or from the content itself
+        match Path::new(input).extension().and_then(|ext| ext.to_str()) {
+            Some("json") => return InputFormat::Json,
+            Some("jsonl") | Some("ndjson") => return InputFormat::JsonLines,
+            Some("csv") => return InputFormat::Csv,
+            _ => (),
+        }
+
+        // A single JSON document is either an array of issues or an object containing them
+        // Anything else is considered as one issue per line
+        match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(serde_json::Value::Array(_)) => InputFormat::Json,
+            Ok(serde_json::Value::Object(object)) if object.contains_key("issues") => {
+                InputFormat::Json
+            }
+            _ => InputFormat::JsonLines,
+        }
+    }
 }
 
 fn has_github_issues(text: &str) -> bool {
-    let fields: [&str; 7] = [
-        "\"issues\"",
-        "\"title\"",
-        "\"number\"",
-        "\"labels\"",
-        "\"state\"",
-        "\"created_at\"",
-        "\"closed_at\"",
+    let fields: [&str; 4] = ["\"title\"", "\"number\"", "\"labels\"", "\"state\""];
+
+    // Timestamps are in snake case in the GitHub API and in camel case in the GitHub CLI
+    let timestamps: [[&str; 2]; 2] = [
+        ["\"created_at\"", "\"createdAt\""],
+        ["\"closed_at\"", "\"closedAt\""],
     ];
 
+    // Issues are either stored in an "issues" field or directly in an array
+    if !text.contains("\"issues\"") && !text.trim_start().starts_with('[') {
+        return false;
+    }
+
     // Return false if one of the fields is not detected
     for field in fields {
         if !text.contains(field) {
@@ -67,36 +674,46 @@ fn has_github_issues(text: &str) -> bool {
         }
     }
 
-    true
+    timestamps
+        .iter()
+        .all(|names| names.iter().any(|name| text.contains(name)))
 }
 
-pub fn parse_json_input(json_file: &Path) -> Result<Repository, IssueParserErr> {
-    // Load the first file into a string
-    let text = match std::fs::read_to_string(json_file) {
-        Ok(text) => text,
-        Err(error) => {
-            let e = IssueParserErr {
-                msg: error.to_string(),
-                kind: ErrKind::Parser,
-            };
-            return Err(e);
-        }
+pub fn read_input(input: &str) -> Result<String, IssueParserErr> {
+    // Load the input into a string, '-' being the standard input
+    let result = if input == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(input)
     };
 
+    match result {
+        Ok(text) => Ok(text),
+        Err(error) => Err(IssueParserErr {
+            msg: error.to_string(),
+            kind: ErrKind::Parser,
+        }),
+    }
+}
+
+fn parse_json_text(text: &str, name: &str) -> Result<Repository, IssueParserErr> {
     // Early read of the JSON read as a string to check if it contains GitHub issues
-    if !has_github_issues(&text) {
+    if !has_github_issues(text) {
         let e = IssueParserErr {
-            msg: format!(
-                "'{}' does not seem to contain GitHub issues.",
-                &json_file.display()
-            ),
+            msg: format!("'{}' does not seem to contain GitHub issues.", name),
             kind: ErrKind::Parser,
         };
         return Err(e);
     }
 
     // Parse the string into a static JSON structure
-    match serde_json::from_str::<Repository>(&text) {
+    let result = if text.trim_start().starts_with('[') {
+        serde_json::from_str::<Vec<Issue>>(text).map(|issues| Repository { issues })
+    } else {
+        serde_json::from_str::<Repository>(text)
+    };
+
+    match result {
         Ok(repository) => Ok(repository),
         Err(e) => Err(IssueParserErr {
             msg: e.to_string(),
@@ -105,20 +722,347 @@ pub fn parse_json_input(json_file: &Path) -> Result<Repository, IssueParserErr>
     }
 }
 
-pub fn print_repo_labels(issues: Vec<Issue>) {
-    let mut repository_labels = Vec::new();
+fn parse_json_lines(text: &str, name: &str) -> Result<Repository, IssueParserErr> {
+    // Parse one issue per line, empty lines are ignored
+    let mut issues: Vec<Issue> = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<Issue>(line) {
+            Ok(issue) => issues.push(issue),
+            Err(e) => {
+                return Err(IssueParserErr {
+                    msg: format!("'{}' line {}: {}", name, index + 1, e),
+                    kind: ErrKind::Parser,
+                });
+            }
+        }
+    }
+
+    if issues.is_empty() {
+        return Err(IssueParserErr {
+            msg: format!("'{}' does not seem to contain GitHub issues.", name),
+            kind: ErrKind::Parser,
+        });
+    }
+
+    Ok(Repository { issues })
+}
+
+pub fn parse_input(input: &str, format: &InputFormat) -> Result<Repository, IssueParserErr> {
+    // Parse a file, or the standard input if '-', into a Repository structure
+    let text: String = read_input(input)?;
+    let name: &str = if input == "-" { "(stdin)" } else { input };
+
+    let format: InputFormat = match format {
+        InputFormat::Auto => InputFormat::detect(input, &text),
+        InputFormat::Json => InputFormat::Json,
+        InputFormat::JsonLines => InputFormat::JsonLines,
+        InputFormat::Csv => InputFormat::Csv,
+    };
+
+    match format {
+        InputFormat::JsonLines => parse_json_lines(&text, name),
+        InputFormat::Csv => parse_csv(&text, name),
+        _ => parse_json_text(&text, name),
+    }
+}
+
+pub fn parse_json_input(json_file: &Path) -> Result<Repository, IssueParserErr> {
+    // Parse a JSON file containing GitHub issues
+    parse_input(&json_file.to_string_lossy(), &InputFormat::Json)
+}
+
+pub fn merge_repositories(repositories: Vec<Repository>) -> (Repository, usize) {
+    // Merge several repositories into one, de-duplicating issues on repository and number
+    // The most recently updated copy of an issue is kept, at the position it was first seen
+    // Issues whose repository is unknown cannot be told apart from other repositories, and are all kept
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut positions: HashMap<(String, i32), usize> = HashMap::new();
+    let mut duplicates: usize = 0;
+
+    for repository in repositories {
+        for issue in repository.issues {
+            let key = match issue.repository() {
+                Some(repository) => (repository, issue.number),
+                None => {
+                    issues.push(issue);
+                    continue;
+                }
+            };
+
+            match positions.get(&key) {
+                Some(&position) => {
+                    duplicates += 1;
+
+                    if issue.updated_at > issues[position].updated_at {
+                        issues[position] = issue;
+                    }
+                }
+                None => {
+                    positions.insert(key, issues.len());
+                    issues.push(issue);
+                }
+            }
+        }
+    }
+
+    (Repository { issues }, duplicates)
+}
+
+// Label mapping rule as written in the mapping file
+#[derive(Deserialize, Debug)]
+struct LabelRuleDef {
+    #[serde(rename = "match")]
+    exact: Option<String>,
+    regex: Option<String>,
+    rename: Option<String>,
+    #[serde(default)]
+    drop: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct LabelMapDef {
+    rules: Vec<LabelRuleDef>,
+}
+
+enum LabelMatcher {
+    Exact(String),
+    Regex(Regex),
+}
+
+enum LabelAction {
+    Rename(String),
+    Drop,
+}
+
+struct LabelRule {
+    matcher: LabelMatcher,
+    action: LabelAction,
+}
+
+// Ordered list of rules used to normalize the labels of the issues
+// The first rule matching a label is applied, unmatched labels are kept as is
+pub struct LabelMap {
+    rules: Vec<LabelRule>,
+}
+
+impl LabelMap {
+    pub fn from_json(text: &str, name: &str) -> Result<LabelMap, IssueParserErr> {
+        // Build the mapping rules from the content of a mapping file
+        let definition: LabelMapDef = match serde_json::from_str(text) {
+            Ok(definition) => definition,
+            Err(error) => {
+                return Err(IssueParserErr {
+                    msg: format!("'{}': {}", name, error),
+                    kind: ErrKind::Input,
+                });
+            }
+        };
+
+        let mut rules: Vec<LabelRule> = Vec::new();
+
+        for (index, rule) in definition.rules.into_iter().enumerate() {
+            let invalid = |msg: &str| IssueParserErr {
+                msg: format!("'{}': rule {} {}", name, index + 1, msg),
+                kind: ErrKind::Input,
+            };
+
+            let matcher: LabelMatcher = match (rule.exact, rule.regex) {
+                (Some(exact), None) => LabelMatcher::Exact(exact),
+                (None, Some(regex)) => match Regex::new(&regex) {
+                    Ok(regex) => LabelMatcher::Regex(regex),
+                    Err(error) => return Err(invalid(&format!("has an invalid regex: {}", error))),
+                },
+                _ => return Err(invalid("must define either 'match' or 'regex'.")),
+            };
+
+            let action: LabelAction = match (rule.rename, rule.drop) {
+                (Some(rename), false) => LabelAction::Rename(rename),
+                (None, true) => LabelAction::Drop,
+                _ => return Err(invalid("must define either 'rename' or 'drop'.")),
+            };
+
+            rules.push(LabelRule { matcher, action });
+        }
+
+        Ok(LabelMap { rules })
+    }
+
+    pub fn from_file(path: &str) -> Result<LabelMap, IssueParserErr> {
+        LabelMap::from_json(&read_input(path)?, path)
+    }
+
+    pub fn map_label(&self, name: &str) -> Option<String> {
+        // Returns the normalized name of a label, or None if the label is dropped
+        for rule in &self.rules {
+            let renamed: String = match &rule.matcher {
+                LabelMatcher::Exact(exact) if exact == name => match &rule.action {
+                    LabelAction::Rename(rename) => String::from(rename),
+                    LabelAction::Drop => return None,
+                },
+                LabelMatcher::Regex(regex) => match regex.captures(name) {
+                    // Capture groups can be referenced in the new name, e.g. "area:$1"
+                    Some(captures) => match &rule.action {
+                        LabelAction::Rename(rename) => {
+                            let mut renamed = String::new();
+                            captures.expand(rename, &mut renamed);
+                            renamed
+                        }
+                        LabelAction::Drop => return None,
+                    },
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            return Some(renamed);
+        }
+
+        Some(String::from(name))
+    }
+
+    pub fn apply(&self, repository: &mut Repository) {
+        // Normalize the labels of every issue, merging labels that end up with the same name
+        for issue in &mut repository.issues {
+            let mut labels: Vec<Label> = Vec::new();
+
+            for mut label in issue.labels.drain(..) {
+                if let Some(name) = self.map_label(&label.name) {
+                    if !labels.iter().any(|l| l.name == name) {
+                        label.name = name;
+                        labels.push(label);
+                    }
+                }
+            }
+
+            issue.labels = labels;
+        }
+    }
+}
+
+pub fn collect_repo_labels(issues: &[Issue], sort: &str) -> Vec<LabelStats> {
+    // Count the open and closed issues of every label used in the repository
+    let mut repository_labels: Vec<LabelStats> = Vec::new();
 
     for issue in issues {
-        for label in issue.labels {
-            if !repository_labels.contains(&label.name) {
-                repository_labels.push(label.name);
+        for label in &issue.labels {
+            let position = match repository_labels.iter().position(|l| l.name == label.name) {
+                Some(position) => position,
+                None => {
+                    repository_labels.push(LabelStats {
+                        name: String::from(&label.name),
+                        color: label.color.clone(),
+                        description: label.description.clone(),
+                        open: 0,
+                        closed: 0,
+                    });
+                    repository_labels.len() - 1
+                }
+            };
+
+            let stats = &mut repository_labels[position];
+
+            // Keep the metadata of the first issue that provides it
+            if stats.color.is_none() {
+                stats.color = label.color.clone();
+            }
+
+            if stats.description.is_none() {
+                stats.description = label.description.clone();
+            }
+
+            if issue.state == IssueState::Closed {
+                stats.closed += 1;
+            } else {
+                stats.open += 1;
             }
         }
     }
 
-    repository_labels.sort_by_key(|a| a.to_lowercase());
+    repository_labels.sort_by_key(|a| a.name.to_lowercase());
 
-    for label in repository_labels {
-        println!("  {}", label);
+    // Most used labels first, ties are kept in alphabetical order
+    if sort == "usage" {
+        repository_labels.sort_by_key(|a| std::cmp::Reverse(a.total()));
     }
+
+    repository_labels
+}
+
+pub fn print_repo_labels(
+    issues: &[Issue],
+    source: &str,
+    format: &str,
+    sort: &str,
+    label_filter: &LabelFilter,
+) -> Result<(), IssueParserErr> {
+    // Only the labels matched by the label filter are listed
+    let mut repository_labels: Vec<LabelStats> = collect_repo_labels(issues, sort);
+    repository_labels.retain(|stats| label_filter.accept_label(&stats.name));
+
+    match format {
+        "csv" => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+
+            for stats in &repository_labels {
+                if let Err(error) = wtr.serialize(stats) {
+                    return Err(IssueParserErr {
+                        msg: error.to_string(),
+                        kind: ErrKind::Writer,
+                    });
+                }
+            }
+
+            if let Err(error) = wtr.flush() {
+                return Err(IssueParserErr {
+                    msg: error.to_string(),
+                    kind: ErrKind::Writer,
+                });
+            }
+        }
+        "json" => match serde_json::to_string_pretty(&repository_labels) {
+            Ok(text) => println!("{}", text),
+            Err(error) => {
+                return Err(IssueParserErr {
+                    msg: error.to_string(),
+                    kind: ErrKind::Writer,
+                });
+            }
+        },
+        _ => {
+            println!("Available labels from {}:", source);
+
+            let width: usize = repository_labels
+                .iter()
+                .map(|stats| stats.name.chars().count())
+                .max()
+                .unwrap_or(0);
+
+            for stats in repository_labels {
+                let mut line: String = format!(
+                    "  {:width$}  open: {:<4} closed: {:<4}",
+                    stats.name,
+                    stats.open,
+                    stats.closed,
+                    width = width
+                );
+
+                if let Some(color) = &stats.color {
+                    line += &format!(" #{}", color);
+                }
+
+                if let Some(description) = &stats.description {
+                    line += &format!(" {}", description);
+                }
+
+                println!("{}", line.trim_end());
+            }
+        }
+    }
+
+    Ok(())
 }
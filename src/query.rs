@@ -0,0 +1,397 @@
+use crate::date::Date;
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+use crate::parser::DateField;
+use crate::parser::Issue;
+use crate::parser::IssueState;
+use crate::parser::LabelPattern;
+use crate::parser::StateReason;
+use crate::timezone::TimeZone;
+use regex::Regex;
+use regex::RegexBuilder;
+use std::fmt;
+
+/*
+    GitHub-style search queries, e.g.
+    'is:open label:bug -label:wontfix author:alice created:>2023-01-01 updated:2023-01..2023-06 "panic in"'.
+    Terms are separated by spaces and must all match, a leading '-' negates a term.
+*/
+
+const QUALIFIERS: [&str; 13] = [
+    "is",
+    "state",
+    "reason",
+    "label",
+    "author",
+    "assignee",
+    "milestone",
+    "no",
+    "created",
+    "updated",
+    "closed",
+    "comments",
+    "reactions",
+];
+
+fn query_err(msg: String) -> IssueParserErr {
+    IssueParserErr {
+        msg,
+        kind: ErrKind::Input,
+    }
+}
+
+// Inclusive range of values, open ends are unbounded
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T: PartialOrd> Range<T> {
+    pub fn contains(&self, value: &T) -> bool {
+        let above_min: bool = match &self.min {
+            Some(min) => min <= value,
+            None => true,
+        };
+        let below_max: bool = match &self.max {
+            Some(max) => value <= max,
+            None => true,
+        };
+
+        above_min && below_max
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Range<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.min, &self.max) {
+            (Some(min), Some(max)) => write!(f, "{}..{}", min, max),
+            (Some(min), None) => write!(f, ">={}", min),
+            (None, Some(max)) => write!(f, "<={}", max),
+            (None, None) => write!(f, "*"),
+        }
+    }
+}
+
+// Resolves a value into the first and last values it covers
+type Resolver<'a, T> = &'a dyn Fn(&str) -> Result<(T, T), IssueParserErr>;
+
+fn parse_range<T: Copy>(
+    value: &str,
+    resolve: Resolver<T>,
+    step: &dyn Fn(T, i64) -> Result<T, IssueParserErr>,
+) -> Result<Range<T>, IssueParserErr> {
+    // Parse comparisons (>X, >=X, <X, <=X), ranges (X..Y, X..*, *..Y) or a single value
+    // Values are resolved into the first and last values they cover, e.g. a whole month for 2023-06
+    if let Some(rest) = value.strip_prefix(">=") {
+        return Ok(Range {
+            min: Some(resolve(rest)?.0),
+            max: None,
+        });
+    }
+
+    if let Some(rest) = value.strip_prefix("<=") {
+        return Ok(Range {
+            min: None,
+            max: Some(resolve(rest)?.1),
+        });
+    }
+
+    if let Some(rest) = value.strip_prefix('>') {
+        return Ok(Range {
+            min: Some(step(resolve(rest)?.1, 1)?),
+            max: None,
+        });
+    }
+
+    if let Some(rest) = value.strip_prefix('<') {
+        return Ok(Range {
+            min: None,
+            max: Some(step(resolve(rest)?.0, -1)?),
+        });
+    }
+
+    if let Some((start, end)) = value.split_once("..") {
+        let min = match start {
+            "*" => None,
+            _ => Some(resolve(start)?.0),
+        };
+        let max = match end {
+            "*" => None,
+            _ => Some(resolve(end)?.1),
+        };
+
+        return Ok(Range { min, max });
+    }
+
+    let (min, max) = resolve(value)?;
+
+    Ok(Range {
+        min: Some(min),
+        max: Some(max),
+    })
+}
+
+pub fn parse_number_range(value: &str) -> Result<Range<i64>, IssueParserErr> {
+    // Range of integers, e.g. 100..250, >=10 or 42
+    let resolve = |text: &str| match text.parse::<i64>() {
+        Ok(number) => Ok((number, number)),
+        Err(_) => Err(query_err(format!(
+            "'{}': '{}' is not a number.",
+            value, text
+        ))),
+    };
+
+    // Strict bounds are one past the number, which must remain an i64
+    let step = |number: i64, step: i64| {
+        number
+            .checked_add(step)
+            .ok_or_else(|| query_err(format!("'{}': number is out of range.", value)))
+    };
+
+    parse_range(value, &resolve, &step)
+}
+
+// Fields that can be tested for absence with 'no:'
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Missing {
+    Label,
+    Assignee,
+    Milestone,
+}
+
+// Tree of conditions evaluated over an issue
+#[derive(Debug, Clone)]
+pub enum Query {
+    And(Vec<Query>),
+    Not(Box<Query>),
+    State(IssueState),
+    Reason(StateReason),
+    Label(LabelPattern),
+    Author(String),
+    Assignee(String),
+    Milestone(String),
+    No(Missing),
+    Date(DateField, Range<Date>),
+    Comments(Range<i64>),
+    Reactions(Range<i64>),
+    Text(String, Regex),
+}
+
+// Term of a query, quoted terms such as "panic in" or -"panic in" are always searched as text
+struct Token {
+    text: String,
+    literal: bool,
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, IssueParserErr> {
+    // Split the query on spaces, except inside double quotes which are removed
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut token = String::new();
+    let mut quoted: bool = false;
+    let mut literal: bool = false;
+    let mut in_token: bool = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => {
+                if !quoted && (token.is_empty() || token == "-") {
+                    literal = true;
+                }
+                quoted = !quoted;
+                in_token = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(Token {
+                        text: std::mem::take(&mut token),
+                        literal,
+                    });
+                    literal = false;
+                    in_token = false;
+                }
+            }
+            c => {
+                token.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if quoted {
+        return Err(query_err(format!(
+            "'{}': unterminated quote in query.",
+            query
+        )));
+    }
+
+    if in_token {
+        tokens.push(Token {
+            text: token,
+            literal,
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn text_query(text: &str) -> Result<Query, IssueParserErr> {
+    // Text is searched as a case-insensitive substring of the title, body and comments
+    match RegexBuilder::new(&regex::escape(text))
+        .case_insensitive(true)
+        .build()
+    {
+        Ok(regex) => Ok(Query::Text(String::from(text), regex)),
+        Err(error) => Err(query_err(error.to_string())),
+    }
+}
+
+impl Query {
+    fn parse_term(term: &str, now: &Date, ignore_case: bool) -> Result<Query, IssueParserErr> {
+        // Terms without a qualifier are searched as text
+        let (qualifier, value) = match term.split_once(':') {
+            Some((qualifier, value)) if QUALIFIERS.contains(&qualifier) => (qualifier, value),
+            Some((qualifier, _)) => {
+                return Err(query_err(format!(
+                    "'{}': unknown qualifier '{}:', expected one of {}. Quote the term to search it as text.",
+                    term,
+                    qualifier,
+                    QUALIFIERS.join(", ")
+                )))
+            }
+            None => return text_query(term),
+        };
+
+        if value.is_empty() {
+            return Err(query_err(format!("'{}': missing value.", term)));
+        }
+
+        match qualifier {
+            "is" | "state" => match IssueState::parse(value) {
+                Ok(state) => Ok(Query::State(state)),
+                Err(_) => Err(query_err(format!(
+                    "'{}': unknown state, expected open or closed.",
+                    term
+                ))),
+            },
+            "reason" => match StateReason::parse(value) {
+                Ok(reason) => Ok(Query::Reason(reason)),
+                Err(_) => Err(query_err(format!(
+                    "'{}': unknown reason, expected completed, not_planned, duplicate or reopened.",
+                    term
+                ))),
+            },
+            "label" => Ok(Query::Label(LabelPattern::parse(value, ignore_case)?)),
+            "author" => Ok(Query::Author(String::from(value))),
+            "assignee" => Ok(Query::Assignee(String::from(value))),
+            "milestone" => Ok(Query::Milestone(String::from(value))),
+            "no" => match value {
+                "label" => Ok(Query::No(Missing::Label)),
+                "assignee" => Ok(Query::No(Missing::Assignee)),
+                "milestone" => Ok(Query::No(Missing::Milestone)),
+                _ => Err(query_err(format!(
+                    "'{}': expected no:label, no:assignee or no:milestone.",
+                    term
+                ))),
+            },
+            "comments" => Ok(Query::Comments(parse_number_range(value)?)),
+            "reactions" => Ok(Query::Reactions(parse_number_range(value)?)),
+            _ => {
+                // Remaining qualifiers are dates, resolved against the reference date
+                let field: DateField = DateField::from_arg(qualifier)?;
+                let resolve = |text: &str| Date::resolve(text, now);
+                let range =
+                    parse_range(value, &resolve, &|date: Date, step| Ok(date.add_days(step)))?;
+
+                Ok(Query::Date(field, range))
+            }
+        }
+    }
+
+    pub fn parse(query: &str, now: &Date, ignore_case: bool) -> Result<Query, IssueParserErr> {
+        let mut terms: Vec<Query> = Vec::new();
+
+        for token in tokenize(query)? {
+            // A leading '-' negates the term
+            let (negated, term) = match token.text.strip_prefix('-') {
+                Some(term) if !term.is_empty() => (true, term),
+                _ => (false, token.text.as_str()),
+            };
+
+            let query = if token.literal {
+                text_query(term)?
+            } else {
+                Query::parse_term(term, now, ignore_case)?
+            };
+
+            if negated {
+                terms.push(Query::Not(Box::new(query)));
+            } else {
+                terms.push(query);
+            }
+        }
+
+        if terms.len() == 1 {
+            return Ok(terms.remove(0));
+        }
+
+        Ok(Query::And(terms))
+    }
+
+    pub fn matches(&self, issue: &Issue, timezone: &TimeZone) -> bool {
+        // Evaluate the query over an issue, dates are compared in the time zone
+        match self {
+            Query::And(queries) => queries.iter().all(|query| query.matches(issue, timezone)),
+            Query::Not(query) => !query.matches(issue, timezone),
+            Query::State(state) => issue.state == *state,
+            Query::Reason(reason) => issue.state_reason == Some(*reason),
+            Query::Label(pattern) => issue.is_labeled(pattern),
+            Query::Author(login) => issue
+                .user
+                .as_ref()
+                .is_some_and(|user| user.login.eq_ignore_ascii_case(login)),
+            Query::Assignee(login) => issue
+                .assignees
+                .iter()
+                .any(|user| user.login.eq_ignore_ascii_case(login)),
+            Query::Milestone(title) => issue
+                .milestone
+                .as_ref()
+                .is_some_and(|milestone| milestone.title.eq_ignore_ascii_case(title)),
+            Query::No(Missing::Label) => issue.labels.is_empty(),
+            Query::No(Missing::Assignee) => issue.assignees.is_empty(),
+            Query::No(Missing::Milestone) => issue.milestone.is_none(),
+            Query::Date(field, range) => issue
+                .timestamp(*field)
+                .is_some_and(|timestamp| range.contains(&timestamp.date_in(timezone))),
+            Query::Comments(range) => range.contains(&(issue.comments.count() as i64)),
+            Query::Reactions(range) => range.contains(&(issue.reactions.total_count as i64)),
+            Query::Text(_, regex) => issue.texts().iter().any(|text| regex.is_match(text)),
+        }
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Query::And(queries) => {
+                let parts: Vec<String> = queries.iter().map(|query| query.to_string()).collect();
+                write!(f, "{}", parts.join(" AND "))
+            }
+            Query::Not(query) => write!(f, "NOT {}", query),
+            Query::State(state) => write!(f, "state = {}", state),
+            Query::Reason(reason) => write!(f, "reason = {}", reason),
+            Query::Label(pattern) => write!(f, "label = {}", pattern),
+            Query::Author(login) => write!(f, "author = '{}'", login),
+            Query::Assignee(login) => write!(f, "assignee = '{}'", login),
+            Query::Milestone(title) => write!(f, "milestone = '{}'", title),
+            Query::No(Missing::Label) => write!(f, "no label"),
+            Query::No(Missing::Assignee) => write!(f, "no assignee"),
+            Query::No(Missing::Milestone) => write!(f, "no milestone"),
+            Query::Date(field, range) => write!(f, "{} in {}", field, range),
+            Query::Comments(range) => write!(f, "comments in {}", range),
+            Query::Reactions(range) => write!(f, "reactions in {}", range),
+            Query::Text(text, _) => write!(f, "text contains '{}'", text),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use csv::ReaderBuilder;
+
+use crate::columns::Field;
+use crate::date::Timestamp;
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+use crate::parser::Comments;
+use crate::parser::Issue;
+use crate::parser::IssueState;
+use crate::parser::Label;
+use crate::parser::Milestone;
+use crate::parser::Repository;
+use crate::parser::StateReason;
+use crate::parser::User;
+
+/*
+    Read back csv files exported by the tool into packed structures.
+    Columns are identified by their default header or their name, e.g. 'Created at' or 'created'.
+    Unknown columns, such as renamed headers, are ignored with a warning.
+*/
+
+const LABEL_SEPARATOR: char = ';';
+
+fn optional(value: &str) -> Option<String> {
+    // Empty cells stand for missing values
+    if value.is_empty() {
+        None
+    } else {
+        Some(String::from(value))
+    }
+}
+
+fn list(value: &str) -> Vec<String> {
+    // Lists of names, e.g. labels or assignees
+    value
+        .split(LABEL_SEPARATOR)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn count(value: &str) -> Result<u32, String> {
+    match value.parse() {
+        Ok(count) => Ok(count),
+        Err(_) => Err(format!("'{}' is not a valid count", value)),
+    }
+}
+
+fn timestamp(value: &str) -> Result<Timestamp, String> {
+    Timestamp::parse(value).map_err(|error| error.msg)
+}
+
+fn column_field(header: &str) -> Option<Field> {
+    // Titles were exported under 'Comment' by older versions
+    if header == "Comment" {
+        return Some(Field::Title);
+    }
+
+    Field::from_column(header)
+}
+
+fn set_field(issue: &mut Issue, field: Field, value: &str) -> Result<(), String> {
+    // Store the value of a cell into the matching issue field
+    // Values computed on export, such as durations, are not read back
+    match field {
+        Field::Id => {
+            issue.number = match value.trim_start_matches('#').parse() {
+                Ok(number) => number,
+                Err(_) => return Err(format!("'{}' is not a valid issue number", value)),
+            }
+        }
+        Field::Title => issue.title = String::from(value),
+        Field::State => issue.state = IssueState::parse(value).map_err(|error| error.msg)?,
+        Field::StateReason => {
+            issue.state_reason = match optional(value) {
+                Some(value) => Some(StateReason::parse(&value).map_err(|error| error.msg)?),
+                None => None,
+            }
+        }
+        Field::Created => issue.created_at = timestamp(value)?,
+        Field::Updated => issue.updated_at = timestamp(value)?,
+        Field::Closed => {
+            issue.closed_at = match optional(value) {
+                Some(value) => Some(timestamp(&value)?),
+                None => None,
+            }
+        }
+        Field::Repository => {
+            // Repositories are exported as 'owner/repository'
+            issue.repository_url = optional(value).map(|repository| {
+                if repository.contains("://") {
+                    repository
+                } else {
+                    format!("https://api.github.com/repos/{}", repository)
+                }
+            })
+        }
+        Field::Url => issue.html_url = optional(value),
+        Field::Labels => {
+            issue.labels = list(value)
+                .into_iter()
+                .map(|name| Label {
+                    name,
+                    ..Default::default()
+                })
+                .collect()
+        }
+        Field::Assignees => {
+            issue.assignees = list(value)
+                .into_iter()
+                .map(|login| User { login })
+                .collect()
+        }
+        Field::Author => issue.user = optional(value).map(|login| User { login }),
+        Field::Milestone => issue.milestone = optional(value).map(|title| Milestone { title }),
+        Field::Comments => issue.comments = Comments::Count(count(value)?),
+        Field::Reactions => issue.reactions.total_count = count(value)?,
+        Field::AgeDays
+        | Field::TimeToClose
+        | Field::BusinessDays
+        | Field::BusinessHours
+        | Field::Match => {}
+    }
+
+    Ok(())
+}
+
+pub fn parse_csv(text: &str, name: &str) -> Result<Repository, IssueParserErr> {
+    let mut rdr = ReaderBuilder::new().from_reader(text.as_bytes());
+
+    let headers: Vec<String> = match rdr.headers() {
+        Ok(headers) => headers.iter().map(String::from).collect(),
+        Err(error) => {
+            return Err(IssueParserErr {
+                msg: error.to_string(),
+                kind: ErrKind::Parser,
+            });
+        }
+    };
+
+    let fields: Vec<Option<Field>> = headers.iter().map(|header| column_field(header)).collect();
+
+    // An issue can only be identified with its number
+    if !fields.contains(&Some(Field::Id)) {
+        let e = IssueParserErr {
+            msg: format!("'{}' does not seem to contain GitHub issues.", name),
+            kind: ErrKind::Parser,
+        };
+        return Err(e);
+    }
+
+    for (header, field) in headers.iter().zip(&fields) {
+        if field.is_none() {
+            eprintln!("'{}': unknown column '{}' is ignored.", name, header);
+        }
+    }
+
+    let mut issues: Vec<Issue> = Vec::new();
+
+    for (index, record) in rdr.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                return Err(IssueParserErr {
+                    msg: error.to_string(),
+                    kind: ErrKind::Parser,
+                });
+            }
+        };
+
+        let mut issue = Issue::default();
+
+        for (field, value) in fields.iter().zip(record.iter()) {
+            let Some(field) = field else {
+                continue;
+            };
+
+            if let Err(msg) = set_field(&mut issue, *field, value) {
+                // The header is the first line of the file
+                return Err(IssueParserErr {
+                    msg: format!("'{}' line {}: {}", name, index + 2, msg),
+                    kind: ErrKind::Parser,
+                });
+            }
+        }
+
+        issues.push(issue);
+    }
+
+    Ok(Repository { issues })
+}
@@ -0,0 +1,187 @@
+use crate::date::Date;
+use crate::date::WorkCalendar;
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+use crate::filters::Filter;
+use crate::parser::Issue;
+use crate::parser::IssueState;
+use crate::timezone::TimeZone;
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+
+/*
+    Aging report of the open issues.
+    Issues are counted per label and per age band, the age being the number of days since their creation,
+    or the number of working days with a calendar.
+*/
+
+// Names and upper bounds of the age bands, in days
+pub const AGE_BANDS: [(&str, Option<i64>); 4] = [
+    ("0-7d", Some(7)),
+    ("8-30d", Some(30)),
+    ("31-90d", Some(90)),
+    ("90d+", None),
+];
+
+const NO_LABEL: &str = "(no label)";
+const TOTAL: &str = "Total";
+
+// Number of open issues of a label in each age band
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct AgingRow {
+    pub label: String,
+    #[serde(rename = "0-7d")]
+    pub week: usize,
+    #[serde(rename = "8-30d")]
+    pub month: usize,
+    #[serde(rename = "31-90d")]
+    pub quarter: usize,
+    #[serde(rename = "90d+")]
+    pub older: usize,
+    pub total: usize,
+}
+
+impl AgingRow {
+    fn new(label: &str) -> AgingRow {
+        AgingRow {
+            label: String::from(label),
+            ..Default::default()
+        }
+    }
+
+    fn add(&mut self, age: i64) {
+        // Issues created after the reference date fall in the first band
+        let band = AGE_BANDS
+            .iter()
+            .position(|(_, max)| match max {
+                Some(max) => age <= *max,
+                None => true,
+            })
+            .unwrap_or(AGE_BANDS.len() - 1);
+
+        match band {
+            0 => self.week += 1,
+            1 => self.month += 1,
+            2 => self.quarter += 1,
+            _ => self.older += 1,
+        }
+
+        self.total += 1;
+    }
+
+    pub fn counts(&self) -> [usize; 4] {
+        [self.week, self.month, self.quarter, self.older]
+    }
+}
+
+pub fn aging_report(
+    issues: &[Issue],
+    filters: &dyn Filter,
+    now: &Date,
+    timezone: &TimeZone,
+    calendar: Option<&WorkCalendar>,
+) -> Vec<AgingRow> {
+    // Count the open issues accepted by the filters, sorted by label name
+    // An issue is counted once per label, and once in the total
+    let mut rows: BTreeMap<String, AgingRow> = BTreeMap::new();
+    let mut total: AgingRow = AgingRow::new(TOTAL);
+
+    for issue in issues {
+        if issue.state != IssueState::Open || !filters.accept(issue) {
+            continue;
+        }
+
+        let created: Date = issue.created_at.date_in(timezone);
+        let age: i64 = match calendar {
+            Some(calendar) => calendar.working_days_between(&created, now),
+            None => created.days_until(now),
+        };
+
+        let mut labels: Vec<&str> = issue
+            .labels
+            .iter()
+            .map(|label| label.name.as_str())
+            .collect();
+
+        if labels.is_empty() {
+            labels.push(NO_LABEL);
+        }
+
+        for label in labels {
+            rows.entry(String::from(label))
+                .or_insert_with(|| AgingRow::new(label))
+                .add(age);
+        }
+
+        total.add(age);
+    }
+
+    let mut report: Vec<AgingRow> = rows.into_values().collect();
+    report.push(total);
+
+    report
+}
+
+pub fn print_aging_report(
+    report: &[AgingRow],
+    source: &str,
+    format: &str,
+) -> Result<(), IssueParserErr> {
+    match format {
+        "csv" => {
+            let mut wtr = csv::Writer::from_writer(std::io::stdout());
+
+            for row in report {
+                if let Err(error) = wtr.serialize(row) {
+                    return Err(IssueParserErr {
+                        msg: error.to_string(),
+                        kind: ErrKind::Writer,
+                    });
+                }
+            }
+
+            if let Err(error) = wtr.flush() {
+                return Err(IssueParserErr {
+                    msg: error.to_string(),
+                    kind: ErrKind::Writer,
+                });
+            }
+        }
+        "json" => match serde_json::to_string_pretty(report) {
+            Ok(text) => println!("{}", text),
+            Err(error) => {
+                return Err(IssueParserErr {
+                    msg: error.to_string(),
+                    kind: ErrKind::Writer,
+                });
+            }
+        },
+        _ => {
+            println!("Open issues by age from {}:", source);
+
+            let width: usize = report
+                .iter()
+                .map(|row| row.label.chars().count())
+                .max()
+                .unwrap_or(0);
+
+            let mut header: String = format!("  {:width$}", "", width = width);
+            for (name, _) in AGE_BANDS {
+                header += &format!("  {:>6}", name);
+            }
+            header += &format!("  {:>6}", TOTAL);
+            println!("{}", header);
+
+            for row in report {
+                let mut line: String = format!("  {:width$}", row.label, width = width);
+                for count in row.counts() {
+                    line += &format!("  {:>6}", count);
+                }
+                line += &format!("  {:>6}", row.total);
+                println!("{}", line);
+            }
+        }
+    }
+
+    Ok(())
+}
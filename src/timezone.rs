@@ -0,0 +1,495 @@
+use crate::date::days_in_month;
+use crate::date::Date;
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+use std::path::Path;
+
+/*
+    Time zones used to convert timestamps into local dates.
+    A time zone is either a fixed UTC offset or an IANA name loaded from the system zoneinfo database.
+    Zoneinfo files list the offset transitions, and end with a POSIX rule used after the last one.
+*/
+
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+
+fn tz_err(msg: String) -> IssueParserErr {
+    IssueParserErr {
+        msg,
+        kind: ErrKind::Date,
+    }
+}
+
+// Day of the year a daylight saving time rule starts or ends on
+#[derive(Debug, Clone, Copy)]
+enum RuleDay {
+    // Jn: day of the year from 1 to 365, February 29th is never counted
+    Julian(i32),
+    // n: day of the year from 0 to 365, February 29th is counted in leap years
+    Ordinal(i32),
+    // Mm.w.d: day d (0 is Sunday) of week w (5 is the last) of month m
+    MonthWeekDay(i32, i32, i32),
+}
+
+impl RuleDay {
+    fn date(&self, year: i32) -> Date {
+        let january_1st: Date = Date {
+            year,
+            month: 1,
+            day: 1,
+        };
+
+        match *self {
+            RuleDay::Julian(day) => {
+                let leap_shift: i64 = if day >= 60 && days_in_month(year, 2) == 29 {
+                    1
+                } else {
+                    0
+                };
+                january_1st.add_days(day as i64 - 1 + leap_shift)
+            }
+            RuleDay::Ordinal(day) => january_1st.add_days(day as i64),
+            RuleDay::MonthWeekDay(month, week, weekday) => {
+                let first: Date = Date {
+                    year,
+                    month,
+                    day: 1,
+                };
+                // Weekdays of the rule start on Sunday, the ones of dates on Monday
+                let first_weekday: i32 = (first.weekday() as i32 + 1) % 7;
+                let mut day: i32 = 1 + (weekday - first_weekday).rem_euclid(7) + (week - 1) * 7;
+
+                while day > days_in_month(year, month) {
+                    day -= 7;
+                }
+
+                Date { day, ..first }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DstRule {
+    offset: i32,
+    start: RuleDay,
+    start_time: i32,
+    end: RuleDay,
+    end_time: i32,
+}
+
+// POSIX TZ rule such as 'CET-1CEST,M3.5.0,M10.5.0/3'
+#[derive(Debug, Clone)]
+struct PosixRule {
+    offset: i32,
+    dst: Option<DstRule>,
+}
+
+struct RuleParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> RuleParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+
+        false
+    }
+
+    fn name(&mut self) -> Option<&'a str> {
+        // Either alphabetic characters, or anything quoted between '<' and '>'
+        let start: usize = self.pos;
+
+        if self.eat('<') {
+            let end: usize = self.text[self.pos..].find('>')? + self.pos;
+            self.pos = end + 1;
+            return Some(&self.text[start + 1..end]);
+        }
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+
+        if self.pos - start < 3 {
+            return None;
+        }
+
+        Some(&self.text[start..self.pos])
+    }
+
+    fn number(&mut self) -> Option<i32> {
+        let start: usize = self.pos;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        self.text[start..self.pos].parse().ok()
+    }
+
+    fn time(&mut self) -> Option<i32> {
+        // [+|-]hh[:mm[:ss]] as a number of seconds
+        let sign: i32 = if self.eat('-') {
+            -1
+        } else {
+            self.eat('+');
+            1
+        };
+
+        // Hours go up to 167 (one week) in the rules of TZif footers
+        let hours: i32 = self.number().filter(|hours| *hours <= 167)?;
+        let mut seconds: i32 = hours.checked_mul(3600)?;
+
+        if self.eat(':') {
+            seconds = seconds.checked_add(self.number()?.checked_mul(60)?)?;
+
+            if self.eat(':') {
+                seconds = seconds.checked_add(self.number()?)?;
+            }
+        }
+
+        sign.checked_mul(seconds)
+    }
+
+    fn rule_day(&mut self) -> Option<(RuleDay, i32)> {
+        // Day of the transition, followed by an optional local time (2 a.m. by default)
+        let day: RuleDay = if self.eat('J') {
+            RuleDay::Julian(self.number().filter(|day| (1..=365).contains(day))?)
+        } else if self.eat('M') {
+            let month: i32 = self.number()?;
+            self.eat('.').then_some(())?;
+            let week: i32 = self.number()?;
+            self.eat('.').then_some(())?;
+            let weekday: i32 = self.number()?;
+
+            if !(1..=12).contains(&month) || !(1..=5).contains(&week) || !(0..=6).contains(&weekday)
+            {
+                return None;
+            }
+
+            RuleDay::MonthWeekDay(month, week, weekday)
+        } else {
+            RuleDay::Ordinal(self.number().filter(|day| (0..=365).contains(day))?)
+        };
+
+        let time: i32 = if self.eat('/') { self.time()? } else { 7200 };
+
+        Some((day, time))
+    }
+
+    fn rule(&mut self) -> Option<PosixRule> {
+        // POSIX offsets are positive west of Greenwich, the opposite of UTC offsets
+        self.name()?;
+        let offset: i32 = -self.time()?;
+
+        if self.peek().is_none() {
+            return Some(PosixRule { offset, dst: None });
+        }
+
+        self.name()?;
+
+        let dst_offset: i32 = match self.peek() {
+            Some(',') => offset + 3600,
+            _ => -self.time()?,
+        };
+
+        self.eat(',').then_some(())?;
+        let (start, start_time) = self.rule_day()?;
+        self.eat(',').then_some(())?;
+        let (end, end_time) = self.rule_day()?;
+
+        if self.peek().is_some() {
+            return None;
+        }
+
+        Some(PosixRule {
+            offset,
+            dst: Some(DstRule {
+                offset: dst_offset,
+                start,
+                start_time,
+                end,
+                end_time,
+            }),
+        })
+    }
+}
+
+impl PosixRule {
+    fn parse(text: &str) -> Option<PosixRule> {
+        RuleParser { text, pos: 0 }.rule()
+    }
+
+    fn offset_at(&self, seconds: i64) -> i32 {
+        let dst: &DstRule = match &self.dst {
+            Some(dst) => dst,
+            None => return self.offset,
+        };
+
+        let year: i32 = Date::from_days((seconds + self.offset as i64).div_euclid(86400)).year;
+
+        // Transitions are written in local time: the start in standard time, the end in daylight time
+        let start: i64 =
+            dst.start.date(year).to_days() * 86400 + dst.start_time as i64 - self.offset as i64;
+        let end: i64 =
+            dst.end.date(year).to_days() * 86400 + dst.end_time as i64 - dst.offset as i64;
+
+        // Daylight saving time spans the new year in the southern hemisphere
+        let in_dst: bool = if start < end {
+            start <= seconds && seconds < end
+        } else {
+            !(end <= seconds && seconds < start)
+        };
+
+        if in_dst {
+            dst.offset
+        } else {
+            self.offset
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeZone {
+    pub name: String,
+    // UTC instants from which a new offset applies, sorted
+    transitions: Vec<(i64, i32)>,
+    // Offset before the first transition
+    initial: i32,
+    // Rule used after the last transition
+    rule: Option<PosixRule>,
+}
+
+struct TzifReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> TzifReader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let slice: &[u8] = self.bytes.get(self.pos..self.pos + n)?;
+        self.pos += n;
+        Some(slice)
+    }
+
+    fn int(&mut self, size: usize) -> Option<i64> {
+        // Big-endian signed integer on 4 or 8 bytes
+        let bytes: &[u8] = self.take(size)?;
+        let mut value: i64 = if bytes[0] & 0x80 != 0 { -1 } else { 0 };
+
+        for byte in bytes {
+            value = (value << 8) | *byte as i64;
+        }
+
+        Some(value)
+    }
+
+    fn counts(&mut self) -> Option<[usize; 6]> {
+        // Header: magic, version, 15 reserved bytes and six counts
+        if self.take(4)? != b"TZif" {
+            return None;
+        }
+
+        self.take(16)?;
+
+        let mut counts: [usize; 6] = [0; 6];
+
+        for count in &mut counts {
+            let bytes: &[u8] = self.take(4)?;
+            *count = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        }
+
+        Some(counts)
+    }
+
+    fn data_len(counts: &[usize; 6], time_size: usize) -> Option<usize> {
+        // Size of the data following a header: transition times and indices, types, abbreviations,
+        // leap seconds and indicators
+        let [isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt] = *counts;
+
+        timecnt
+            .checked_mul(time_size + 1)?
+            .checked_add(typecnt.checked_mul(6)?)?
+            .checked_add(charcnt)?
+            .checked_add(leapcnt.checked_mul(time_size + 4)?)?
+            .checked_add(isstdcnt)?
+            .checked_add(isutcnt)
+    }
+}
+
+impl TimeZone {
+    pub fn utc() -> TimeZone {
+        TimeZone::fixed("UTC", 0)
+    }
+
+    pub fn fixed(name: &str, offset: i32) -> TimeZone {
+        TimeZone {
+            name: String::from(name),
+            transitions: Vec::new(),
+            initial: offset,
+            rule: None,
+        }
+    }
+
+    fn parse_offset(text: &str) -> Option<i32> {
+        // Parse '+HH', '+HH:MM' or '+HHMM' into a number of seconds
+        let sign: i32 = match text.chars().next()? {
+            '+' => 1,
+            '-' => -1,
+            _ => return None,
+        };
+
+        let digits: String = text[1..].replace(':', "");
+
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let (hours, minutes): (i32, i32) = match digits.len() {
+            1 | 2 => (digits.parse().ok()?, 0),
+            4 => (digits[..2].parse().ok()?, digits[2..].parse().ok()?),
+            _ => return None,
+        };
+
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+
+        Some(sign * (hours * 3600 + minutes * 60))
+    }
+
+    pub fn from_posix(name: &str, rule: &str) -> Result<TimeZone, IssueParserErr> {
+        match PosixRule::parse(rule) {
+            Some(rule) => Ok(TimeZone {
+                name: String::from(name),
+                transitions: Vec::new(),
+                initial: rule.offset,
+                rule: Some(rule),
+            }),
+            None => Err(tz_err(format!("'{}': invalid time zone rule.", rule))),
+        }
+    }
+
+    pub fn from_tzif(name: &str, bytes: &[u8]) -> Result<TimeZone, IssueParserErr> {
+        let invalid = || tz_err(format!("'{}': invalid zoneinfo file.", name));
+
+        let mut reader = TzifReader { bytes, pos: 0 };
+        let mut counts: [usize; 6] = reader.counts().ok_or_else(invalid)?;
+        let version: u8 = bytes[4];
+        let mut time_size: usize = 4;
+
+        // Version 2 and later repeat the data with 64-bit times after the version 1 data
+        if version >= b'2' {
+            let skipped: usize = TzifReader::data_len(&counts, time_size).ok_or_else(invalid)?;
+            reader.take(skipped).ok_or_else(invalid)?;
+            counts = reader.counts().ok_or_else(invalid)?;
+            time_size = 8;
+        }
+
+        let [isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt] = counts;
+
+        // The counts must fit in the file before anything is allocated from them
+        let data_len: usize = TzifReader::data_len(&counts, time_size).ok_or_else(invalid)?;
+
+        if typecnt == 0 || data_len > bytes.len() - reader.pos {
+            return Err(invalid());
+        }
+
+        let mut times: Vec<i64> = Vec::with_capacity(timecnt);
+        for _ in 0..timecnt {
+            times.push(reader.int(time_size).ok_or_else(invalid)?);
+        }
+
+        let indices: Vec<u8> = reader.take(timecnt).ok_or_else(invalid)?.to_vec();
+
+        let mut offsets: Vec<i32> = Vec::with_capacity(typecnt);
+        for _ in 0..typecnt {
+            offsets.push(reader.int(4).ok_or_else(invalid)? as i32);
+            reader.take(2).ok_or_else(invalid)?;
+        }
+
+        reader
+            .take(charcnt + leapcnt * (time_size + 4) + isstdcnt + isutcnt)
+            .ok_or_else(invalid)?;
+
+        let mut transitions: Vec<(i64, i32)> = Vec::with_capacity(timecnt);
+        for (time, index) in times.into_iter().zip(indices) {
+            let offset: i32 = *offsets.get(index as usize).ok_or_else(invalid)?;
+            transitions.push((time, offset));
+        }
+
+        // The footer holds the rule to use after the last transition, between newlines
+        let rule: Option<PosixRule> = if version >= b'2' {
+            let footer: String = String::from_utf8_lossy(&bytes[reader.pos..]).to_string();
+            PosixRule::parse(footer.trim_matches('\n'))
+        } else {
+            None
+        };
+
+        Ok(TimeZone {
+            name: String::from(name),
+            transitions,
+            initial: offsets[0],
+            rule,
+        })
+    }
+
+    pub fn parse(name: &str) -> Result<TimeZone, IssueParserErr> {
+        // Build a time zone from 'UTC', a fixed offset such as '+02:00' or an IANA name such as 'Europe/Paris'
+        if matches!(name, "UTC" | "utc" | "Z" | "GMT") {
+            return Ok(TimeZone::utc());
+        }
+
+        let offset: &str = name
+            .strip_prefix("UTC")
+            .or_else(|| name.strip_prefix("GMT"))
+            .unwrap_or(name);
+
+        if offset.starts_with(['+', '-']) {
+            return match TimeZone::parse_offset(offset) {
+                Some(offset) => Ok(TimeZone::fixed(name, offset)),
+                None => Err(tz_err(format!("'{}': invalid UTC offset.", name))),
+            };
+        }
+
+        // Names are relative paths inside the zoneinfo database
+        if name.is_empty() || name.starts_with('/') || name.split('/').any(|part| part == "..") {
+            return Err(tz_err(format!("'{}': invalid time zone name.", name)));
+        }
+
+        let dir: String = std::env::var("TZDIR").unwrap_or_else(|_| String::from(ZONEINFO_DIR));
+
+        match std::fs::read(Path::new(&dir).join(name)) {
+            Ok(bytes) => TimeZone::from_tzif(name, &bytes),
+            Err(_) => Err(tz_err(format!(
+                "'{}': unknown time zone, expected UTC, an offset such as +02:00 or a name from {}.",
+                name, dir
+            ))),
+        }
+    }
+
+    pub fn offset_at(&self, seconds: i64) -> i32 {
+        // UTC offset in seconds at an instant
+        let index: usize = self
+            .transitions
+            .partition_point(|(time, _)| *time <= seconds);
+
+        if index == self.transitions.len() {
+            if let Some(rule) = &self.rule {
+                return rule.offset_at(seconds);
+            }
+        }
+
+        match index {
+            0 => self.initial,
+            _ => self.transitions[index - 1].1,
+        }
+    }
+}
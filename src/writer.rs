@@ -1,25 +1,190 @@
 use csv::Writer;
 
+use crate::columns::Column;
+use crate::columns::Value;
+use crate::date::Timestamp;
+use crate::date::WorkCalendar;
 use crate::err::ErrKind;
 use crate::err::IssueParserErr;
-use crate::filters::Filters;
+use crate::filters::Filter;
+use crate::html::html_report;
+use crate::input::Args;
+use crate::markdown::markdown_table;
+use crate::markdown::Changelog;
 use crate::parser::Issue;
+use crate::parser::IssueSort;
+use crate::parser::IssueState;
+use crate::timezone::TimeZone;
+use crate::xlsx::build_workbook;
+use crate::xlsx::Sheet;
+use crate::xlsx::SheetGroup;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
 
 /*
-    From packed stuctures, write the serialized data into a csv file.
+    From packed stuctures, write the serialized data into a csv, JSON, JSON Lines, XLSX, Markdown or HTML file.
 */
 
-const CSV_HEADER: [&str; 5] = ["ID", "Created at", "Last update", "State", "Comment"];
 const CSV_EXT: &str = ".csv";
 
-pub fn build_output_filename(filename: String) -> String {
-    let extensions: [&str; 7] = [".txt", ".csv", ".text", ".dat", ".log", ".xls", ".xlsx"];
+fn writer_err(msg: String) -> IssueParserErr {
+    IssueParserErr {
+        msg,
+        kind: ErrKind::Writer,
+    }
+}
 
-    // If no filename was specified, always return 'out.csv'
-    if filename == "out.csv" {
+// Formats of the exported file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+    JsonLines,
+    Xlsx,
+    Markdown,
+    Html,
+}
+
+impl OutputFormat {
+    pub fn from_arg(arg: &str, filename: &str) -> OutputFormat {
+        // Build the output format from the --format argument, or from the output extension
+        match arg {
+            "csv" => OutputFormat::Csv,
+            "json" => OutputFormat::Json,
+            "jsonl" => OutputFormat::JsonLines,
+            "xlsx" => OutputFormat::Xlsx,
+            "markdown" => OutputFormat::Markdown,
+            "html" => OutputFormat::Html,
+            _ => match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+                Some("json") => OutputFormat::Json,
+                Some("jsonl") | Some("ndjson") => OutputFormat::JsonLines,
+                Some("xlsx") => OutputFormat::Xlsx,
+                Some("md") | Some("markdown") => OutputFormat::Markdown,
+                Some("html") | Some("htm") => OutputFormat::Html,
+                _ => OutputFormat::Csv,
+            },
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Csv => CSV_EXT,
+            OutputFormat::Json => ".json",
+            OutputFormat::JsonLines => ".jsonl",
+            OutputFormat::Xlsx => ".xlsx",
+            OutputFormat::Markdown => ".md",
+            OutputFormat::Html => ".html",
+        }
+    }
+}
+
+// Settings used to format the exported data
+pub struct ExportSettings {
+    pub timezone: TimeZone,
+    // Open issues are measured until this instant
+    pub now: Timestamp,
+    // Durations in working time are only exported with a calendar
+    pub calendar: Option<WorkCalendar>,
+    // Exported columns, in order
+    pub columns: Vec<Column>,
+    // Issues are exported in their input order without sort
+    pub sort: Option<IssueSort>,
+    // Additional sheets of XLSX workbooks
+    pub sheets: Option<SheetGroup>,
+    // Markdown outputs are written as a changelog instead of a table
+    pub changelog: Option<Changelog>,
+}
+
+impl ExportSettings {
+    pub fn from_args(args: &Args) -> Result<ExportSettings, IssueParserErr> {
+        // Factory function that builds export settings from user inputs
+        // Without --columns, durations in working time and matches are appended to the default columns
+        let columns: Vec<Column> = match &args.columns {
+            Some(columns) => Column::parse_list(columns)?,
+            None => Column::defaults(args.business_time, args.search_snippet),
+        };
+
+        let changelog: Option<Changelog> = match (&args.changelog_sections, args.changelog) {
+            (Some(path), true) => Some(Changelog::from_file(path)?),
+            (None, true) => Some(Changelog::default()),
+            (_, false) => None,
+        };
+
+        let business: bool = columns.iter().any(|column| column.field.is_business());
+
+        let calendar: Option<WorkCalendar> = if business {
+            Some(WorkCalendar::new(
+                &args.weekend,
+                &args.working_hours,
+                args.holidays.as_deref(),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(ExportSettings {
+            timezone: TimeZone::parse(&args.timezone)?,
+            now: Timestamp::reference(&args.now)?,
+            calendar,
+            columns,
+            sort: args.sort.as_deref().map(IssueSort::parse).transpose()?,
+            sheets: args.sheets.as_deref().and_then(SheetGroup::from_arg),
+            changelog,
+        })
+    }
+
+    pub fn open_until(&self, issue: &Issue) -> Timestamp {
+        // End of the time an issue has been open
+        issue.closed_at.unwrap_or(self.now)
+    }
+
+    pub fn business_time(&self, calendar: &WorkCalendar, issue: &Issue) -> (i64, f64) {
+        // Working days and working hours an issue has been open
+        let end: Timestamp = self.open_until(issue);
+
+        let days: i64 = calendar.working_days_between(
+            &issue.created_at.date_in(&self.timezone),
+            &end.date_in(&self.timezone),
+        );
+        let seconds: i64 =
+            calendar.working_seconds_between(&issue.created_at, &end, &self.timezone);
+
+        (days, seconds as f64 / 3600.0)
+    }
+}
+
+pub fn build_output_filename(filename: String, format: &OutputFormat) -> String {
+    let extensions: [&str; 13] = [
+        ".txt",
+        ".csv",
+        ".text",
+        ".dat",
+        ".log",
+        ".xlsx",
+        ".json",
+        ".jsonl",
+        ".ndjson",
+        ".md",
+        ".markdown",
+        ".html",
+        ".htm",
+    ];
+
+    // The standard output has no extension
+    if filename == "-" {
         return filename;
     }
 
+    // If no filename was specified, always return 'out' with the extension of the format
+    if filename == "out.csv" {
+        return String::from("out") + format.extension();
+    }
+
     // If a known extension was specified, we return the filename as is
     for ext in extensions {
         if filename.contains(ext) {
@@ -27,44 +192,65 @@ pub fn build_output_filename(filename: String) -> String {
         }
     }
 
-    // Add the 'csv' extension if the file does not contain one
-    filename + CSV_EXT
+    // Add the extension of the format if the file does not contain one
+    filename + format.extension()
+}
+
+fn create_output(filename: &str) -> Result<Box<dyn Write>, IssueParserErr> {
+    // The export is written to the standard output with '-', to be piped into other tools
+    if filename == "-" {
+        return Ok(Box::new(BufWriter::new(std::io::stdout())));
+    }
+
+    let file = File::create(filename).map_err(|error| writer_err(error.to_string()))?;
+
+    Ok(Box::new(BufWriter::new(file)))
+}
+
+fn write_content(filename: &str, content: &[u8]) -> Result<(), IssueParserErr> {
+    let mut wtr = create_output(filename)?;
+
+    wtr.write_all(content)
+        .and_then(|_| wtr.flush())
+        .map_err(|error| writer_err(error.to_string()))
 }
 
 pub fn write_csv(
-    issues: Vec<Issue>,
+    mut issues: Vec<Issue>,
     filename: &str,
-    filters: Filters,
+    filters: &dyn Filter,
+    settings: &ExportSettings,
 ) -> Result<(), IssueParserErr> {
-    let mut wtr = match Writer::from_path(filename) {
-        Ok(writer) => writer,
-        Err(error) => {
-            return Err(IssueParserErr {
-                msg: error.to_string(),
-                kind: ErrKind::Writer,
-            });
-        }
-    };
+    let mut wtr = Writer::from_writer(create_output(filename)?);
 
-    // The header is always the same
-    if let Err(error) = wtr.write_record(CSV_HEADER) {
+    let header: Vec<&str> = settings
+        .columns
+        .iter()
+        .map(|column| column.header.as_str())
+        .collect();
+
+    if let Err(error) = wtr.write_record(&header) {
         return Err(IssueParserErr {
             msg: error.to_string(),
             kind: ErrKind::Writer,
         });
     };
 
+    if let Some(sort) = &settings.sort {
+        sort.sort(&mut issues);
+    }
+
     // Parse the array of issues
     for issue in issues {
         // Only write the issues that are not rejected by the filters
         if !filters.reject(&issue) {
-            if let Err(error) = wtr.write_record(&[
-                issue.number.to_string(),
-                Issue::format_date(issue.created_at),
-                Issue::format_date(issue.updated_at),
-                issue.state,
-                issue.title,
-            ]) {
+            let record: Vec<String> = settings
+                .columns
+                .iter()
+                .map(|column| column.value(&issue, settings, filters).to_string())
+                .collect();
+
+            if let Err(error) = wtr.write_record(&record) {
                 return Err(IssueParserErr {
                     msg: error.to_string(),
                     kind: ErrKind::Writer,
@@ -82,3 +268,212 @@ pub fn write_csv(
 
     Ok(())
 }
+
+// Exported fields of an issue, serialized as an object in the order of the columns
+// Keys are the column names, or their header when it was renamed
+struct Record<'a> {
+    columns: &'a [Column],
+    values: Vec<Value>,
+}
+
+impl Serialize for Record<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+
+        for (column, value) in self.columns.iter().zip(&self.values) {
+            map.serialize_entry(column.key(), value)?;
+        }
+
+        map.end()
+    }
+}
+
+fn exported_issues(
+    mut issues: Vec<Issue>,
+    filters: &dyn Filter,
+    settings: &ExportSettings,
+) -> Vec<Issue> {
+    // Only export the issues that are not rejected by the filters, in the sort order
+    if let Some(sort) = &settings.sort {
+        sort.sort(&mut issues);
+    }
+
+    issues.retain(|issue| !filters.reject(issue));
+    issues
+}
+
+fn row(issue: &Issue, filters: &dyn Filter, settings: &ExportSettings) -> Vec<Value> {
+    settings
+        .columns
+        .iter()
+        .map(|column| column.value(issue, settings, filters))
+        .collect()
+}
+
+fn build_records<'a>(
+    issues: Vec<Issue>,
+    filters: &dyn Filter,
+    settings: &'a ExportSettings,
+) -> Vec<Record<'a>> {
+    exported_issues(issues, filters, settings)
+        .iter()
+        .map(|issue| Record {
+            columns: &settings.columns,
+            values: row(issue, filters, settings),
+        })
+        .collect()
+}
+
+fn write_records(filename: &str, records: &[Record], lines: bool) -> Result<(), IssueParserErr> {
+    // A JSON array, or one JSON object per line
+    let mut wtr = create_output(filename)?;
+
+    let written = if lines {
+        records.iter().try_for_each(|record| {
+            serde_json::to_writer(&mut wtr, record)?;
+            writeln!(wtr).map_err(serde_json::Error::io)
+        })
+    } else {
+        serde_json::to_writer_pretty(&mut wtr, records)
+            .and_then(|_| writeln!(wtr).map_err(serde_json::Error::io))
+    };
+
+    written.map_err(|error| writer_err(error.to_string()))?;
+    wtr.flush().map_err(|error| writer_err(error.to_string()))
+}
+
+pub fn write_json(
+    issues: Vec<Issue>,
+    filename: &str,
+    filters: &dyn Filter,
+    settings: &ExportSettings,
+) -> Result<(), IssueParserErr> {
+    let records: Vec<Record> = build_records(issues, filters, settings);
+
+    write_records(filename, &records, false)
+}
+
+pub fn write_jsonl(
+    issues: Vec<Issue>,
+    filename: &str,
+    filters: &dyn Filter,
+    settings: &ExportSettings,
+) -> Result<(), IssueParserErr> {
+    let records: Vec<Record> = build_records(issues, filters, settings);
+
+    write_records(filename, &records, true)
+}
+
+pub fn write_xlsx(
+    issues: Vec<Issue>,
+    filename: &str,
+    filters: &dyn Filter,
+    settings: &ExportSettings,
+) -> Result<(), IssueParserErr> {
+    // Every exported issue is in the first sheet, and in the sheets of its labels or state
+    let issues: Vec<Issue> = exported_issues(issues, filters, settings);
+
+    let mut sheets: Vec<Sheet> = vec![Sheet {
+        name: String::from("Issues"),
+        rows: issues
+            .iter()
+            .map(|issue| row(issue, filters, settings))
+            .collect(),
+    }];
+
+    match settings.sheets {
+        Some(SheetGroup::Label) => {
+            let mut groups: BTreeMap<String, Vec<Vec<Value>>> = BTreeMap::new();
+
+            for issue in &issues {
+                let mut names: Vec<String> = issue
+                    .labels
+                    .iter()
+                    .map(|label| label.name.clone())
+                    .collect();
+
+                if names.is_empty() {
+                    names.push(String::from("(no label)"));
+                }
+
+                for name in names {
+                    groups
+                        .entry(name)
+                        .or_default()
+                        .push(row(issue, filters, settings));
+                }
+            }
+
+            sheets.extend(groups.into_iter().map(|(name, rows)| Sheet { name, rows }));
+        }
+        Some(SheetGroup::State) => {
+            for state in [IssueState::Open, IssueState::Closed] {
+                let rows: Vec<Vec<Value>> = issues
+                    .iter()
+                    .filter(|issue| issue.state == state)
+                    .map(|issue| row(issue, filters, settings))
+                    .collect();
+
+                if !rows.is_empty() {
+                    sheets.push(Sheet {
+                        name: state.to_string(),
+                        rows,
+                    });
+                }
+            }
+        }
+        None => {}
+    }
+
+    let header: Vec<&str> = settings
+        .columns
+        .iter()
+        .map(|column| column.header.as_str())
+        .collect();
+
+    write_content(filename, &build_workbook(&header, &sheets)?)
+}
+
+pub fn write_markdown(
+    issues: Vec<Issue>,
+    filename: &str,
+    filters: &dyn Filter,
+    settings: &ExportSettings,
+) -> Result<(), IssueParserErr> {
+    let issues: Vec<Issue> = exported_issues(issues, filters, settings);
+
+    let content: String = match &settings.changelog {
+        Some(changelog) => changelog.render(&issues),
+        None => markdown_table(&issues, filters, settings),
+    };
+
+    write_content(filename, content.as_bytes())
+}
+
+pub fn write_html(
+    issues: Vec<Issue>,
+    filename: &str,
+    filters: &dyn Filter,
+    settings: &ExportSettings,
+) -> Result<(), IssueParserErr> {
+    let issues: Vec<Issue> = exported_issues(issues, filters, settings);
+
+    write_content(filename, html_report(&issues, filters, settings).as_bytes())
+}
+
+pub fn write_output(
+    issues: Vec<Issue>,
+    filename: &str,
+    format: &OutputFormat,
+    filters: &dyn Filter,
+    settings: &ExportSettings,
+) -> Result<(), IssueParserErr> {
+    match format {
+        OutputFormat::Csv => write_csv(issues, filename, filters, settings),
+        OutputFormat::Json => write_json(issues, filename, filters, settings),
+        OutputFormat::JsonLines => write_jsonl(issues, filename, filters, settings),
+        OutputFormat::Xlsx => write_xlsx(issues, filename, filters, settings),
+        OutputFormat::Markdown => write_markdown(issues, filename, filters, settings),
+        OutputFormat::Html => write_html(issues, filename, filters, settings),
+    }
+}
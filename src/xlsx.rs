@@ -0,0 +1,295 @@
+use crate::columns::Value;
+use crate::date::Date;
+use crate::err::IssueParserErr;
+use crate::zip::ZipArchive;
+
+/*
+    Minimal XLSX (Office Open XML) workbook writer.
+    Every sheet starts with a bold, frozen header row with an auto-filter, followed by typed cells:
+    numbers as numbers, dates as dates and other values as text.
+*/
+
+const XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#;
+const MAIN_NAMESPACE: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const RELATIONSHIPS_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+// Excel counts days from 1899-12-30, 25569 days before 1970-01-01
+const SERIAL_EPOCH: i64 = 25569;
+const MAX_SHEET_NAME: usize = 31;
+const MAX_COLUMN_WIDTH: usize = 60;
+
+// Cell styles defined in styles.xml
+const DATE_STYLE: u32 = 1;
+const HEADER_STYLE: u32 = 2;
+
+const STYLES: &str = r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="1"><numFmt numFmtId="164" formatCode="yyyy\-mm\-dd"/></numFmts><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="3"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#;
+
+// Sheet of a workbook, the rows follow the header of the workbook
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<Value>>,
+}
+
+// Issues are split into additional sheets by label or by state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SheetGroup {
+    Label,
+    State,
+}
+
+impl SheetGroup {
+    pub fn from_arg(arg: &str) -> Option<SheetGroup> {
+        match arg {
+            "label" => Some(SheetGroup::Label),
+            "state" => Some(SheetGroup::State),
+            _ => None,
+        }
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    // Escape the XML special characters, control characters are not allowed in XML
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+pub fn column_name(index: usize) -> String {
+    // Letters of a column from its index, e.g. 0 for A and 27 for AB
+    let mut name = String::new();
+    let mut index: usize = index + 1;
+
+    while index > 0 {
+        let remainder: usize = (index - 1) % 26;
+        name.insert(0, (b'A' + remainder as u8) as char);
+        index = (index - 1) / 26;
+    }
+
+    name
+}
+
+pub fn serial_date(date: &Date) -> i64 {
+    date.to_days() + SERIAL_EPOCH
+}
+
+pub fn sheet_name(name: &str, used: &[String]) -> String {
+    // Sheet names are limited to 31 characters, cannot contain :\/?*[] and must be unique ignoring case
+    let cleaned: String = name
+        .chars()
+        .map(|c| if ":\\/?*[]".contains(c) { '_' } else { c })
+        .collect();
+    let cleaned: &str = cleaned.trim_matches('\'');
+    let base: String = if cleaned.is_empty() {
+        String::from("Sheet")
+    } else {
+        cleaned.chars().take(MAX_SHEET_NAME).collect()
+    };
+
+    let mut candidate: String = base.clone();
+    let mut count: usize = 2;
+
+    while used
+        .iter()
+        .any(|name| name.to_lowercase() == candidate.to_lowercase())
+    {
+        let suffix: String = format!(" ({})", count);
+        candidate = base
+            .chars()
+            .take(MAX_SHEET_NAME - suffix.len())
+            .collect::<String>()
+            + &suffix;
+        count += 1;
+    }
+
+    candidate
+}
+
+fn cell(reference: &str, value: &Value) -> String {
+    match value {
+        Value::Empty => String::new(),
+        Value::Integer(number) => format!(r#"<c r="{}"><v>{}</v></c>"#, reference, number),
+        Value::Decimal(number) => format!(r#"<c r="{}"><v>{}</v></c>"#, reference, number),
+        Value::Date(date) => format!(
+            r#"<c r="{}" s="{}"><v>{}</v></c>"#,
+            reference,
+            DATE_STYLE,
+            serial_date(date)
+        ),
+        Value::Text(_) | Value::List(_) => text_cell(reference, &value.to_string(), None),
+    }
+}
+
+fn text_cell(reference: &str, text: &str, style: Option<u32>) -> String {
+    // Strings are written inline rather than in a shared strings table
+    let style: String = style.map_or(String::new(), |style| format!(r#" s="{}""#, style));
+
+    format!(
+        r#"<c r="{}"{} t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+        reference,
+        style,
+        escape(text)
+    )
+}
+
+fn worksheet(header: &[&str], rows: &[Vec<Value>]) -> String {
+    let last_column: String = column_name(header.len().saturating_sub(1));
+    let range: String = format!("A1:{}{}", last_column, rows.len() + 1);
+
+    // Columns are as wide as their longest value
+    let mut widths: Vec<usize> = header.iter().map(|name| name.chars().count()).collect();
+
+    for row in rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            let length: usize = match value {
+                Value::Date(_) => 10,
+                value => value.to_string().chars().count(),
+            };
+            *width = (*width).max(length).min(MAX_COLUMN_WIDTH);
+        }
+    }
+
+    let mut xml: String = format!(
+        r#"{}<worksheet xmlns="{}" xmlns:r="{}"><dimension ref="{}"/>"#,
+        XML_HEADER, MAIN_NAMESPACE, RELATIONSHIPS_NAMESPACE, range
+    );
+
+    // The header row stays visible when scrolling
+    xml.push_str(r#"<sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews>"#);
+
+    if !widths.is_empty() {
+        xml.push_str("<cols>");
+        for (index, width) in widths.iter().enumerate() {
+            xml.push_str(&format!(
+                r#"<col min="{}" max="{}" width="{}" customWidth="1"/>"#,
+                index + 1,
+                index + 1,
+                width + 2
+            ));
+        }
+        xml.push_str("</cols>");
+    }
+
+    xml.push_str(r#"<sheetData><row r="1">"#);
+    for (index, name) in header.iter().enumerate() {
+        let reference: String = format!("{}1", column_name(index));
+        xml.push_str(&text_cell(&reference, name, Some(HEADER_STYLE)));
+    }
+    xml.push_str("</row>");
+
+    for (number, row) in rows.iter().enumerate() {
+        let line: usize = number + 2;
+
+        xml.push_str(&format!(r#"<row r="{}">"#, line));
+        for (index, value) in row.iter().enumerate() {
+            let reference: String = format!("{}{}", column_name(index), line);
+            xml.push_str(&cell(&reference, value));
+        }
+        xml.push_str("</row>");
+    }
+
+    xml.push_str("</sheetData>");
+    xml.push_str(&format!(r#"<autoFilter ref="{}"/>"#, range));
+    xml.push_str("</worksheet>");
+
+    xml
+}
+
+pub fn build_workbook(header: &[&str], sheets: &[Sheet]) -> Result<Vec<u8>, IssueParserErr> {
+    // Package the sheets into a zip archive with the parts required by spreadsheet applications
+    let mut names: Vec<String> = Vec::new();
+    for sheet in sheets {
+        let name: String = sheet_name(&sheet.name, &names);
+        names.push(name);
+    }
+
+    let last_column: String = column_name(header.len().saturating_sub(1));
+
+    let mut content_types: String = format!(
+        r#"{}<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+        XML_HEADER
+    );
+    let mut workbook: String = format!(
+        r#"{}<workbook xmlns="{}" xmlns:r="{}"><sheets>"#,
+        XML_HEADER, MAIN_NAMESPACE, RELATIONSHIPS_NAMESPACE
+    );
+    let mut defined_names = String::new();
+    let mut relationships: String = format!(
+        r#"{}<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        XML_HEADER
+    );
+
+    for (index, (sheet, name)) in sheets.iter().zip(&names).enumerate() {
+        let id: usize = index + 1;
+
+        content_types.push_str(&format!(
+            r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+            id
+        ));
+        workbook.push_str(&format!(
+            r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+            escape(name),
+            id,
+            id
+        ));
+        // Range of the auto-filter, with quotes doubled in the sheet name
+        defined_names.push_str(&format!(
+            r#"<definedName name="_xlnm._FilterDatabase" localSheetId="{}" hidden="1">'{}'!$A$1:${}${}</definedName>"#,
+            index,
+            escape(&name.replace('\'', "''")),
+            last_column,
+            sheet.rows.len() + 1
+        ));
+        relationships.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="{}/worksheet" Target="worksheets/sheet{}.xml"/>"#,
+            id, RELATIONSHIPS_NAMESPACE, id
+        ));
+    }
+
+    content_types.push_str("</Types>");
+    workbook.push_str(&format!(
+        "</sheets><definedNames>{}</definedNames></workbook>",
+        defined_names
+    ));
+    relationships.push_str(&format!(
+        r#"<Relationship Id="rId{}" Type="{}/styles" Target="styles.xml"/></Relationships>"#,
+        sheets.len() + 1,
+        RELATIONSHIPS_NAMESPACE
+    ));
+
+    let package_relationships: String = format!(
+        r#"{}<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="{}/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+        XML_HEADER, RELATIONSHIPS_NAMESPACE
+    );
+
+    let mut archive: ZipArchive = ZipArchive::new();
+    archive.add_file("[Content_Types].xml", content_types.as_bytes())?;
+    archive.add_file("_rels/.rels", package_relationships.as_bytes())?;
+    archive.add_file("xl/workbook.xml", workbook.as_bytes())?;
+    archive.add_file("xl/_rels/workbook.xml.rels", relationships.as_bytes())?;
+    archive.add_file(
+        "xl/styles.xml",
+        format!("{}{}", XML_HEADER, STYLES).as_bytes(),
+    )?;
+
+    for (index, sheet) in sheets.iter().enumerate() {
+        archive.add_file(
+            &format!("xl/worksheets/sheet{}.xml", index + 1),
+            worksheet(header, &sheet.rows).as_bytes(),
+        )?;
+    }
+
+    archive.finish()
+}
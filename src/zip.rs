@@ -0,0 +1,162 @@
+use crate::err::ErrKind;
+use crate::err::IssueParserErr;
+
+/*
+    Minimal zip archive support used to write XLSX workbooks.
+    Files are stored without compression, with a fixed modification date for reproducible archives.
+    ZIP64 is not supported: archives are limited to 65535 files and 4 GiB.
+*/
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+const END_SIGNATURE: u32 = 0x06054b50;
+// Version 2.0 of the format, required for folders
+const VERSION: u16 = 20;
+// MS-DOS date of 1980-01-01 at midnight, the earliest one
+const DOS_DATE: u16 = (1 << 5) | 1;
+const DOS_TIME: u16 = 0;
+
+fn crc32_table() -> [u32; 256] {
+    let mut table: [u32; 256] = [0; 256];
+
+    for (n, entry) in table.iter_mut().enumerate() {
+        let mut c: u32 = n as u32;
+
+        for _ in 0..8 {
+            c = if c & 1 == 1 {
+                0xEDB88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+        }
+
+        *entry = c;
+    }
+
+    table
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    // Checksum of the content of a file, as stored in its headers
+    let table: [u32; 256] = crc32_table();
+    let mut crc: u32 = 0xFFFFFFFF;
+
+    for byte in data {
+        crc = table[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+
+    crc ^ 0xFFFFFFFF
+}
+
+fn zip_err(msg: String) -> IssueParserErr {
+    IssueParserErr {
+        msg,
+        kind: ErrKind::Writer,
+    }
+}
+
+fn push_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+// Archive built in memory, files are added in order
+#[derive(Default)]
+pub struct ZipArchive {
+    data: Vec<u8>,
+    central_directory: Vec<u8>,
+    entries: u16,
+}
+
+impl ZipArchive {
+    pub fn new() -> ZipArchive {
+        ZipArchive::default()
+    }
+
+    fn push_common_header(buffer: &mut Vec<u8>, crc: u32, size: u32, name_len: u16) {
+        // Fields shared by the local and central headers, from the version needed to the extra field length
+        push_u16(buffer, VERSION);
+        push_u16(buffer, 0);
+        push_u16(buffer, 0);
+        push_u16(buffer, DOS_TIME);
+        push_u16(buffer, DOS_DATE);
+        push_u32(buffer, crc);
+        push_u32(buffer, size);
+        push_u32(buffer, size);
+        push_u16(buffer, name_len);
+        push_u16(buffer, 0);
+    }
+
+    pub fn add_file(&mut self, name: &str, content: &[u8]) -> Result<(), IssueParserErr> {
+        // Sizes, offsets and counts are checked against the limits of the headers
+        let name_len: u16 = u16::try_from(name.len()).map_err(|_| {
+            zip_err(format!(
+                "'{}': file name is too long for a zip archive.",
+                name
+            ))
+        })?;
+        let size: u32 = u32::try_from(content.len())
+            .map_err(|_| zip_err(format!("'{}': file is too large for a zip archive.", name)))?;
+        let offset: u32 = u32::try_from(self.data.len()).map_err(|_| {
+            zip_err(format!(
+                "'{}': zip archive is too large to add the file.",
+                name
+            ))
+        })?;
+        let entries: u16 = self.entries.checked_add(1).ok_or_else(|| {
+            zip_err(format!(
+                "'{}': zip archive has too many files to add the file.",
+                name
+            ))
+        })?;
+        let crc: u32 = crc32(content);
+
+        push_u32(&mut self.data, LOCAL_HEADER_SIGNATURE);
+        ZipArchive::push_common_header(&mut self.data, crc, size, name_len);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(content);
+
+        // The central directory repeats the header, with the position of the file
+        push_u32(&mut self.central_directory, CENTRAL_HEADER_SIGNATURE);
+        push_u16(&mut self.central_directory, VERSION);
+        ZipArchive::push_common_header(&mut self.central_directory, crc, size, name_len);
+        // Comment length, disk number, internal and external attributes
+        push_u16(&mut self.central_directory, 0);
+        push_u16(&mut self.central_directory, 0);
+        push_u16(&mut self.central_directory, 0);
+        push_u32(&mut self.central_directory, 0);
+        push_u32(&mut self.central_directory, offset);
+        self.central_directory.extend_from_slice(name.as_bytes());
+
+        self.entries = entries;
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, IssueParserErr> {
+        // Append the central directory and its end record
+        let too_large = |_| {
+            zip_err(String::from(
+                "zip archive is too large, ZIP64 is not supported.",
+            ))
+        };
+        let offset: u32 = u32::try_from(self.data.len()).map_err(too_large)?;
+        let size: u32 = u32::try_from(self.central_directory.len()).map_err(too_large)?;
+
+        self.data.append(&mut self.central_directory);
+
+        push_u32(&mut self.data, END_SIGNATURE);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, 0);
+        push_u16(&mut self.data, self.entries);
+        push_u16(&mut self.data, self.entries);
+        push_u32(&mut self.data, size);
+        push_u32(&mut self.data, offset);
+        push_u16(&mut self.data, 0);
+
+        Ok(self.data)
+    }
+}
@@ -11,6 +11,7 @@ use std::process::Command;
 
 const CORRECT_JSON: &str = "tests/doc/cocotb-cocotb_issues.json";
 const WRONG_JSON: &str = "tests/doc/bogus.json";
+const SMALL_JSON: &str = "tests/doc/teobiton-issue-tracking.json";
 const OUTPUT_ARG: &str = "--output=outunext";
 const EXT_OUTPUT_ARG: &str = "--output=outext.csv";
 
@@ -149,3 +150,689 @@ fn run_print_labels() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn run_with_merged_inputs() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "merged.csv";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "tests/doc/teobiton-*.json", "-o", filename])
+        .assert()
+        .stdout(predicate::str::contains(
+            "Merged 3 duplicate issues from 2 inputs.",
+        ))
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(content.lines().count(), 4);
+
+    Ok(())
+}
+
+#[test]
+fn run_with_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "stdin.csv";
+    let lines: String = fs::read_to_string("tests/doc/teobiton-issue-tracking.jsonl")?;
+
+    assert_cmd::Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args(["-", "-o", filename, "--state", "open"])
+        .write_stdin(lines)
+        .assert()
+        .stdout(predicate::str::contains("Built stdin.csv from -."))
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(content.lines().count(), 3);
+
+    assert_cmd::Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args(["-", SMALL_JSON, "-"])
+        .write_stdin("[]")
+        .assert()
+        .stderr(predicate::str::contains(
+            "'-': the standard input can only be given once.",
+        ))
+        .success();
+    Ok(())
+}
+
+#[test]
+fn run_with_csv_export() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "refiltered.csv";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            "tests/doc/teobiton-issue-tracking.csv",
+            "-o",
+            filename,
+            "--state",
+            "closed",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        "ID,Created at,Last update,State,Title,Repository\n2,2023-05-16,2023-05-16,closed,Improve error management,\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_print_labels_csv() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "--print-labels", "--list-format=csv"])
+        .assert()
+        .stdout(predicate::str::contains(
+            "name,color,description,open,closed\nenhancement,a2eeef,Improve the current design,1,1\n",
+        ))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn run_with_label_map() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "--label-map=tests/doc/label-map.json",
+            "--print-labels",
+        ])
+        .assert()
+        .stdout(predicate::str::contains(
+            "type:feature  open: 2    closed: 1",
+        ))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn run_with_relative_dates() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "relative.csv";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--now=2023-05-18",
+            "--start-date=2d",
+            "--end-date=yesterday",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        "ID,Created at,Last update,State,Title,Repository\n2,2023-05-16,2023-05-16,closed,Improve error management,teobiton/issue-tracking\n3,2023-05-17,2023-05-17,open,Introduce filtering capabilities,teobiton/issue-tracking\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_with_stale() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "stale.csv";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "-o", filename, "--now=2023-05-20", "--stale=4d"])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        "ID,Created at,Last update,State,Title,Repository\n1,2023-05-15,2023-05-15,open,Move from StructOpt to App,teobiton/issue-tracking\n2,2023-05-16,2023-05-16,closed,Improve error management,teobiton/issue-tracking\n"
+    );
+
+    // In working days, the weekend and Ascension Day do not count
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--now=2023-05-22",
+            "--stale=4d",
+            "--business-time",
+            "--holidays=tests/doc/holidays.txt",
+            "--columns=id",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(content, "ID\n1\n");
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "--older-than=90"])
+        .assert()
+        .stderr(predicate::str::contains("age is not at the right format"));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_engagement_sort() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "engagement.csv";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            "tests/doc/reactions.jsonl",
+            "-o",
+            filename,
+            "--min-reactions=+1:1",
+            "--sort=comments",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        "ID,Created at,Last update,State,Title,Repository\n22,2023-07-02,2023-07-03,open,Dark theme for the HTML report,\n21,2023-07-01,2023-07-20,open,Export to Excel,\n"
+    );
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args(["tests/doc/reactions.jsonl", "--sort=reaction"])
+        .assert()
+        .stderr(predicate::str::contains("Did you mean 'reactions'?"));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "columns.csv";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--now=2023-05-20",
+            "--columns=id,title=Summary,state_reason,labels,author,age_days,time_to_close,url",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        "ID,Summary,State reason,Labels,Author,Age (days),Time to close (days),URL\n\
+        1,Move from StructOpt to App,,enhancement,teobiton,5,,https://github.com/teobiton/issue-tracking/issues/1\n\
+        2,Improve error management,completed,enhancement,teobiton,4,0.0,https://github.com/teobiton/issue-tracking/issues/2\n\
+        3,Introduce filtering capabilities,,feature,teobiton,3,,https://github.com/teobiton/issue-tracking/issues/3\n"
+    );
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "--columns=id,titel"])
+        .assert()
+        .stderr(predicate::str::contains("Did you mean 'title'?"));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_json_output() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "issues.json";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--state=closed",
+            "--columns=id,title=summary,labels,closed,time_to_close,milestone",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        r#"[
+  {
+    "id": 2,
+    "summary": "Improve error management",
+    "labels": [
+      "enhancement"
+    ],
+    "closed": "2023-05-16",
+    "time_to_close": 0.0,
+    "milestone": null
+  }
+]
+"#
+    );
+
+    // The extension of the format is added when missing
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            "lines",
+            "--format=jsonl",
+            "--columns=id,state",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string("lines.jsonl")?;
+    fs::remove_file("lines.jsonl")?;
+
+    assert_eq!(
+        content,
+        "{\"id\":1,\"state\":\"open\"}\n{\"id\":2,\"state\":\"closed\"}\n{\"id\":3,\"state\":\"open\"}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_with_xlsx_output() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "issues.xlsx";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "-o", filename, "--sheets=state"])
+        .assert()
+        .success();
+
+    let content: Vec<u8> = fs::read(filename)?;
+    fs::remove_file(filename)?;
+
+    // Stored zip archive with one sheet for all issues and one per state
+    let text: String = String::from_utf8_lossy(&content).to_string();
+
+    assert!(content.starts_with(b"PK\x03\x04"));
+    assert!(text.contains(r#"<sheet name="Issues" sheetId="1" r:id="rId1"/><sheet name="open" sheetId="2" r:id="rId2"/><sheet name="closed" sheetId="3" r:id="rId3"/>"#));
+    assert!(text.contains(r#"<c r="B2" s="1"><v>45061</v></c>"#));
+
+    // Legacy workbooks would only be csv text with another extension
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "-o", "report.xls"])
+        .assert()
+        .stderr(predicate::str::contains(
+            "report.xls: .xls workbooks are not supported, use .xlsx instead.",
+        ));
+
+    assert!(!Path::new("report.xls").exists());
+
+    Ok(())
+}
+
+#[test]
+fn run_with_markdown_output() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "-o", "issues.md", "--columns=id,title,state"])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string("issues.md")?;
+    fs::remove_file("issues.md")?;
+
+    assert_eq!(
+        content,
+        "| ID | Title | State |\n\
+        | --- | --- | --- |\n\
+        | [#1](https://github.com/teobiton/issue-tracking/issues/1) | Move from StructOpt to App | open |\n\
+        | [#2](https://github.com/teobiton/issue-tracking/issues/2) | Improve error management | closed |\n\
+        | [#3](https://github.com/teobiton/issue-tracking/issues/3) | Introduce filtering capabilities | open |\n"
+    );
+
+    // Changelogs only list the issues closed within the dates
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            "notes",
+            "--changelog",
+            "--start-date=2023-05-16",
+            "--end-date=2023-05-16",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string("notes.md")?;
+    fs::remove_file("notes.md")?;
+
+    assert_eq!(
+        content,
+        "## Features\n\n- Improve error management ([#2](https://github.com/teobiton/issue-tracking/issues/2))\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_with_html_output() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "-o", "report.html", "--now=2023-05-20"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Built report.html from"));
+
+    let content: String = fs::read_to_string("report.html")?;
+    fs::remove_file("report.html")?;
+
+    assert!(content.starts_with("<!DOCTYPE html>"));
+    assert!(content.contains("3 issues, as of 2023-05-20."));
+    assert!(
+        content.contains(r#"<a href="https://github.com/teobiton/issue-tracking/issues/2">#2</a>"#)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_with_stdout() -> Result<(), Box<dyn std::error::Error>> {
+    // Only the export is written to the standard output, status messages go to the standard error
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "tests/doc/teobiton-*.json",
+            "-o",
+            "-",
+            "--columns=id,state",
+        ])
+        .assert()
+        .stdout("ID,State\n1,open\n2,closed\n3,open\n")
+        .stderr(predicate::str::contains(
+            "Merged 3 duplicate issues from 2 inputs.",
+        ))
+        .stderr(predicate::str::contains("Built (stdout) from"))
+        .success();
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "-o", "-", "--columns=id", "--format=jsonl"])
+        .assert()
+        .stdout("{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n")
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn run_aging_report() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "--aging-report",
+            "--list-format=csv",
+            "--now=2023-06-20",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "label,0-7d,8-30d,31-90d,90d+,total\nenhancement,0,0,1,0,1\nfeature,0,0,1,0,1\nTotal,0,0,2,0,2\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_date_field() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "closed.csv";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--date-field=closed",
+            "--start-date=2023-05-01",
+            "--end-date=created:2023-05-31",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(content.lines().count(), 2);
+    assert!(content.contains("Improve error management"));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_timezone() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "timezone.csv";
+
+    // Issue 1 was created on 2023-05-15 at 14:39 UTC, already the next day in UTC+10
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--timezone=+10:00",
+            "--date-field=created",
+            "--start-date=2023-05-16",
+            "--end-date=2023-05-16",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        "ID,Created at,Last update,State,Title,Repository\n1,2023-05-16,2023-05-16,open,Move from StructOpt to App,teobiton/issue-tracking\n"
+    );
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([SMALL_JSON, "--timezone=Mars/Olympus_Mons"])
+        .assert()
+        .stderr(predicate::str::contains("unknown time zone"))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn run_with_business_time() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "business.csv";
+
+    Command::cargo_bin("issue-tracking")
+        .expect("binary exists")
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--business-time",
+            "--holidays=tests/doc/holidays.txt",
+            "--now=2023-05-19",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        "ID,Created at,Last update,State,Title,Repository,Business days open,Business hours open\n\
+         1,2023-05-15,2023-05-15,open,Move from StructOpt to App,teobiton/issue-tracking,3,18.3\n\
+         2,2023-05-16,2023-05-16,closed,Improve error management,teobiton/issue-tracking,0,0.0\n\
+         3,2023-05-17,2023-05-17,open,Introduce filtering capabilities,teobiton/issue-tracking,1,7.3\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_with_label_expression() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "labelexpr.csv";
+
+    Command::cargo_bin("issue-tracking")?
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--label=feature|enhancement",
+            "--label=!feature",
+            "--verbose",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Filter: labels match ('feature' OR 'enhancement') AND NOT 'feature'",
+        ));
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    let ids: Vec<&str> = content
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').next().unwrap_or(""))
+        .collect();
+    assert_eq!(ids, vec!["1", "2"]);
+
+    Ok(())
+}
+
+#[test]
+fn run_print_labels_with_pattern() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("issue-tracking")?
+        .args([
+            SMALL_JSON,
+            "--print-labels",
+            "--list-format=csv",
+            "--ignore-case",
+            "--label=ENH*",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "name,color,description,open,closed\n\
+             enhancement,a2eeef,Improve the current design,1,1\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_search_snippet() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "search.csv";
+
+    Command::cargo_bin("issue-tracking")?
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--search=exitfailure",
+            "--search-snippet",
+        ])
+        .assert()
+        .success();
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(
+        content,
+        "ID,Created at,Last update,State,Title,Repository,Match\n\
+         2,2023-05-16,2023-05-16,closed,Improve error management,teobiton/issue-tracking,\
+         \"...o do it using either Err() or **ExitFailure** crates, or something else.\"\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_with_query() -> Result<(), Box<dyn std::error::Error>> {
+    let filename: &str = "query.csv";
+
+    Command::cargo_bin("issue-tracking")?
+        .args([
+            SMALL_JSON,
+            "-o",
+            filename,
+            "--query=is:open -label:feature author:teobiton \"structopt\"",
+            "--verbose",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Filter: query state = open AND NOT label = 'feature' AND author = 'teobiton' AND text contains 'structopt'",
+        ));
+
+    let content: String = fs::read_to_string(filename)?;
+    fs::remove_file(filename)?;
+
+    assert_eq!(content.lines().count(), 2);
+    assert!(content.contains("1,2023-05-15,2023-05-15,open,Move from StructOpt to App"));
+
+    Command::cargo_bin("issue-tracking")?
+        .args([SMALL_JSON, "-o", filename, "--query=reviewer:teobiton"])
+        .assert()
+        .stderr(predicate::str::contains("unknown qualifier 'reviewer:'"));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_wrong_state() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("issue-tracking")?
+        .args([SMALL_JSON, "--state=opened"])
+        .assert()
+        .stderr(predicate::str::contains("Did you mean 'open'?"));
+
+    Command::cargo_bin("issue-tracking")?
+        .args([SMALL_JSON, "--number=>9223372036854775807"])
+        .assert()
+        .stderr(predicate::str::contains("number is out of range."))
+        .success();
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use issue_tracking::columns::Column;
+use issue_tracking::columns::Field;
+use issue_tracking::columns::Value;
+use issue_tracking::date::Date;
+use issue_tracking::date::Timestamp;
+use issue_tracking::date::WorkCalendar;
+use issue_tracking::filters::Filters;
+use issue_tracking::filters::SearchFilter;
+use issue_tracking::parser::Issue;
+use issue_tracking::parser::IssueState;
+use issue_tracking::parser::Label;
+use issue_tracking::parser::StateReason;
+use issue_tracking::parser::User;
+use issue_tracking::timezone::TimeZone;
+use issue_tracking::writer::ExportSettings;
+
+/*
+   Tests for exported columns.
+*/
+
+fn settings(columns: Vec<Column>) -> Result<ExportSettings, Box<dyn std::error::Error>> {
+    Ok(ExportSettings {
+        timezone: TimeZone::utc(),
+        now: Timestamp::parse("2023-06-30T12:00:00Z")?,
+        calendar: Some(WorkCalendar::default()),
+        columns,
+        sort: None,
+        sheets: None,
+        changelog: None,
+    })
+}
+
+#[test]
+fn test_parse_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let columns: Vec<Column> = Column::parse_list("id,title=Summary, state_reason ,labels=Tags")?;
+
+    assert_eq!(
+        columns,
+        vec![
+            Column::new(Field::Id),
+            Column {
+                field: Field::Title,
+                header: String::from("Summary"),
+            },
+            Column::new(Field::StateReason),
+            Column {
+                field: Field::Labels,
+                header: String::from("Tags"),
+            },
+        ]
+    );
+    assert_eq!(Column::new(Field::Title).header, "Title");
+    assert_eq!(Field::TimeToClose.name(), "time_to_close");
+
+    // Structured formats name the values after the field, unless the header was renamed
+    assert_eq!(columns[0].key(), "id");
+    assert_eq!(columns[1].key(), "Summary");
+
+    // Default columns, with durations in working time and matches appended
+    let headers = |columns: Vec<Column>| -> Vec<String> {
+        columns.into_iter().map(|column| column.header).collect()
+    };
+
+    assert_eq!(
+        headers(Column::defaults(false, false)),
+        [
+            "ID",
+            "Created at",
+            "Last update",
+            "State",
+            "Title",
+            "Repository"
+        ]
+    );
+    assert_eq!(
+        headers(Column::defaults(true, true))[6..],
+        ["Business days open", "Business hours open", "Match"]
+    );
+
+    assert!(Column::parse_list("id,,title").is_err());
+    assert!(Column::parse_list("id,title=").is_err());
+    assert!(Column::parse_list("id,lables")
+        .unwrap_err()
+        .to_string()
+        .contains("Did you mean 'labels'?"));
+
+    Ok(())
+}
+
+#[test]
+fn test_column_values() -> Result<(), Box<dyn std::error::Error>> {
+    let mut issue: Issue = Issue {
+        number: 7,
+        title: String::from("Panic in the writer"),
+        repository_url: Some(String::from(
+            "https://api.github.com/repos/teobiton/issue-tracking",
+        )),
+        labels: vec![
+            Label {
+                name: String::from("bug"),
+                ..Default::default()
+            },
+            Label {
+                name: String::from("ui"),
+                ..Default::default()
+            },
+        ],
+        state: IssueState::Closed,
+        state_reason: Some(StateReason::NotPlanned),
+        user: Some(User {
+            login: String::from("alice"),
+        }),
+        created_at: Timestamp::parse("2023-06-01T10:00:00Z")?,
+        updated_at: Timestamp::parse("2023-06-03T10:00:00Z")?,
+        closed_at: Some(Timestamp::parse("2023-06-03T22:00:00Z")?),
+        ..Default::default()
+    };
+
+    let settings: ExportSettings = settings(vec![])?;
+    let filters: Filters = Filters::builder()
+        .filter(SearchFilter::from_text("writer")?)
+        .build();
+
+    let cases: [(Field, Value); 14] = [
+        (Field::Id, Value::Integer(7)),
+        (Field::State, Value::Text(String::from("closed"))),
+        (Field::StateReason, Value::Text(String::from("not_planned"))),
+        (Field::Created, Value::Date(Date::parse("2023-06-01")?)),
+        (Field::Closed, Value::Date(Date::parse("2023-06-03")?)),
+        (
+            Field::Labels,
+            Value::List(vec![String::from("bug"), String::from("ui")]),
+        ),
+        (Field::Assignees, Value::List(vec![])),
+        (Field::Author, Value::Text(String::from("alice"))),
+        (Field::Milestone, Value::Empty),
+        (
+            Field::Url,
+            Value::Text(String::from(
+                "https://github.com/teobiton/issue-tracking/issues/7",
+            )),
+        ),
+        (Field::AgeDays, Value::Integer(29)),
+        (Field::TimeToClose, Value::Decimal(2.5)),
+        (Field::BusinessDays, Value::Integer(1)),
+        (
+            Field::Match,
+            Value::Text(String::from("Panic in the **writer**")),
+        ),
+    ];
+
+    for (field, expected) in cases {
+        assert_eq!(
+            Column::new(field).value(&issue, &settings, &filters),
+            expected,
+            "{}",
+            field.name()
+        );
+    }
+
+    // Durations are rounded the same way in every format
+    issue.closed_at = Some(Timestamp::parse("2023-06-01T10:20:00Z")?);
+    let hours: Value = Column::new(Field::BusinessHours).value(&issue, &settings, &filters);
+
+    assert_eq!(hours, Value::Decimal(0.3));
+    assert_eq!(serde_json::to_string(&hours)?, "0.3");
+
+    // Values are written as text in csv files
+    assert_eq!(
+        Column::new(Field::Labels)
+            .value(&issue, &settings, &filters)
+            .to_string(),
+        "bug; ui"
+    );
+    assert_eq!(Value::Decimal(16.0).to_string(), "16.0");
+    assert_eq!(Value::Empty.to_string(), "");
+
+    // Values keep their type in JSON
+    assert_eq!(
+        serde_json::to_string(&[
+            Value::Empty,
+            Value::Integer(3),
+            Value::Decimal(2.5),
+            Value::Date(Date::parse("2023-06-01")?),
+            Value::List(vec![String::from("bug")]),
+        ])?,
+        r#"[null,3,2.5,"2023-06-01",["bug"]]"#
+    );
+
+    Ok(())
+}
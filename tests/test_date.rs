@@ -1,4 +1,8 @@
+use issue_tracking::date::parse_age;
 use issue_tracking::date::Date;
+use issue_tracking::date::Timestamp;
+use issue_tracking::date::WorkCalendar;
+use issue_tracking::timezone::TimeZone;
 
 #[test]
 fn test_build_date() -> Result<(), Box<dyn std::error::Error>> {
@@ -40,7 +44,7 @@ fn test_build_date() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut temp_date: Date;
     for i in 0..5 {
-        temp_date = Date::date_str(dates_str[i]);
+        temp_date = Date::date_str(dates_str[i])?;
         assert_eq!(temp_date.year, dates_result[i].year);
         assert_eq!(temp_date.month, dates_result[i].month);
         assert_eq!(temp_date.day, dates_result[i].day);
@@ -103,3 +107,234 @@ fn test_compare_date() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_invalid_dates() -> Result<(), Box<dyn std::error::Error>> {
+    let dates_str: [&str; 7] = [
+        "2023-13-01",
+        "2023-02-30",
+        "2023-02-29",
+        "2023-00-10",
+        "2023-1-10",
+        "23-01-10",
+        "2023-01-1a",
+    ];
+
+    for date_str in dates_str {
+        assert!(Date::parse(date_str).is_err(), "{} was accepted", date_str);
+    }
+
+    // Leap years
+    assert!(Date::parse("2024-02-29").is_ok());
+    assert!(Date::parse("2000-02-29").is_ok());
+    assert!(Date::parse("1900-02-29").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_date_arithmetic() -> Result<(), Box<dyn std::error::Error>> {
+    let date: Date = Date::parse("2023-12-30")?;
+
+    assert_eq!(date.add_days(3), Date::parse("2024-01-02")?);
+    assert_eq!(date.add_days(-365), Date::parse("2022-12-30")?);
+    assert_eq!(Date::parse("2024-02-28")?.add_days(1).day, 29);
+    assert_eq!(date.days_until(&Date::parse("2024-03-01")?), 62);
+    assert_eq!(Date::parse("1970-01-01")?.to_days(), 0);
+    assert_eq!(Date::from_days(19523), Date::parse("2023-06-15")?);
+
+    // 2023-06-18 was a Sunday
+    assert_eq!(Date::parse("2023-06-18")?.weekday(), 6);
+    assert_eq!(Date::parse("2023-06-19")?.weekday(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_timestamp() -> Result<(), Box<dyn std::error::Error>> {
+    let utc: Timestamp = Timestamp::parse("2023-05-15T14:39:59Z")?;
+    let paris: Timestamp = Timestamp::parse("2023-05-15T16:39:59+02:00")?;
+    let late: Timestamp = Timestamp::parse("2023-05-15T23:30:00.123-05:00")?;
+
+    assert_eq!(utc, paris);
+    assert_eq!(utc.to_string(), "2023-05-15T14:39:59Z");
+    assert_eq!(paris.to_string(), "2023-05-15T16:39:59+02:00");
+
+    // The date is the one at the offset of the timestamp
+    assert_eq!(late.date(), Date::parse("2023-05-15")?);
+    assert_eq!(late.utc_date(), Date::parse("2023-05-16")?);
+    assert!(utc < late);
+
+    assert_eq!(Timestamp::parse("2023-05-15")?.seconds(), 1684108800);
+    assert_eq!(utc.days_until(&utc.add_days(3)), 3);
+
+    let invalid: [&str; 6] = [
+        "2023-05-15T25:00:00Z",
+        "2023-05-15T14:39Z",
+        "2023-05-15T14:39:59",
+        "2023-05-15T14:39:59+2",
+        "2023-02-30T14:39:59Z",
+        "2023",
+    ];
+
+    for timestamp in invalid {
+        assert!(
+            Timestamp::parse(timestamp).is_err(),
+            "{} was accepted",
+            timestamp
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_resolve_expressions() -> Result<(), Box<dyn std::error::Error>> {
+    // 2026-04-15 is a Wednesday
+    let now: Date = Date::parse("2026-04-15")?;
+
+    let expressions: [(&str, &str, &str); 14] = [
+        ("2023-06-18", "2023-06-18", "2023-06-18"),
+        ("today", "2026-04-15", "2026-04-15"),
+        ("yesterday", "2026-04-14", "2026-04-14"),
+        ("30d", "2026-03-16", "2026-03-16"),
+        ("2w", "2026-04-01", "2026-04-01"),
+        ("1y", "2025-04-15", "2025-04-15"),
+        ("this-week", "2026-04-13", "2026-04-19"),
+        ("last-month", "2026-03-01", "2026-03-31"),
+        ("this-quarter", "2026-04-01", "2026-06-30"),
+        ("last-quarter", "2026-01-01", "2026-03-31"),
+        ("last-year", "2025-01-01", "2025-12-31"),
+        ("2026-W14", "2026-03-30", "2026-04-05"),
+        ("2024-02", "2024-02-01", "2024-02-29"),
+        ("2023", "2023-01-01", "2023-12-31"),
+    ];
+
+    for (expr, start, end) in expressions {
+        let (first, last) = Date::resolve(expr, &now)?;
+        assert_eq!(first.to_string(), start, "{}", expr);
+        assert_eq!(last.to_string(), end, "{}", expr);
+    }
+
+    // Months are clamped to their last day
+    assert_eq!(
+        Date::resolve("1m", &Date::parse("2026-03-31")?)?.0,
+        Date::parse("2026-02-28")?
+    );
+
+    // 2026 has 53 ISO weeks, 2025 only 52
+    assert!(Date::resolve("2026-W53", &now).is_ok());
+    assert!(Date::resolve("2025-W53", &now).is_err());
+    assert!(Date::resolve("2025-W00", &now).is_err());
+    assert!(Date::resolve("next-month", &now).is_err());
+    assert!(Date::resolve("d", &now).is_err());
+    assert!(Date::resolve("2023-13", &now).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_business_days() -> Result<(), Box<dyn std::error::Error>> {
+    let calendar: WorkCalendar = WorkCalendar::new("sat,sun", "09:00-17:00", None)?;
+
+    // From Friday to Monday, only Monday is a working day
+    let friday: Date = Date::parse("2023-06-16")?;
+    let monday: Date = Date::parse("2023-06-19")?;
+
+    assert_eq!(calendar.working_days_between(&friday, &monday), 1);
+    assert_eq!(calendar.working_days_between(&friday, &friday), 0);
+    assert_eq!(
+        calendar.working_days_between(&friday, &Date::parse("2023-06-30")?),
+        10
+    );
+
+    // One hour on Friday evening and one hour on Monday morning
+    let created: Timestamp = Timestamp::parse("2023-06-16T16:00:00Z")?;
+    let closed: Timestamp = Timestamp::parse("2023-06-19T10:00:00Z")?;
+
+    assert_eq!(
+        calendar.working_seconds_between(&created, &closed, &TimeZone::utc()),
+        7200
+    );
+
+    // Working hours are in local time
+    assert_eq!(
+        calendar.working_seconds_between(&created, &closed, &TimeZone::parse("+02:00")?),
+        3 * 3600
+    );
+
+    // Weekend on Friday and Saturday
+    let calendar: WorkCalendar = WorkCalendar::new("fri,sat", "08:30-12:00", None)?;
+    assert_eq!(calendar.working_days_between(&friday, &monday), 2);
+    assert!(WorkCalendar::new("caturday", "09:00-17:00", None).is_err());
+    assert!(WorkCalendar::new("saturnine", "09:00-17:00", None).is_err());
+    assert!(WorkCalendar::new("sat,monkey", "09:00-17:00", None).is_err());
+    assert!(WorkCalendar::new("sa", "09:00-17:00", None).is_err());
+    assert!(WorkCalendar::new("Saturday, sun", "09:00-17:00", None).is_ok());
+    assert!(WorkCalendar::new("none", "09:00-17:00", None).is_ok());
+    assert!(WorkCalendar::new("sat,sun", "17:00-09:00", None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_holidays() -> Result<(), Box<dyn std::error::Error>> {
+    let calendar: WorkCalendar =
+        WorkCalendar::new("sat,sun", "09:00-17:00", Some("tests/doc/holidays.txt"))?;
+
+    assert!(!calendar.is_working_day(&Date::parse("2023-05-18")?));
+    assert!(calendar.is_working_day(&Date::parse("2023-05-17")?));
+
+    // Both days of Christmas and New Year
+    let holidays = WorkCalendar::load_holidays("tests/doc/holidays.ics")?;
+    let expected: Vec<Date> = vec![
+        Date::parse("2023-12-25")?,
+        Date::parse("2023-12-26")?,
+        Date::parse("2024-01-01")?,
+    ];
+
+    assert_eq!(holidays.into_iter().collect::<Vec<Date>>(), expected);
+    assert!(WorkCalendar::parse_holidays("2023-13-01 Bogus", "bogus").is_err());
+
+    // Malformed event dates are skipped
+    let malformed: &str = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART:222é1225\nEND:VEVENT\n\
+        BEGIN:VEVENT\nDTSTART;VALUE=DATE:2023122x\nEND:VEVENT\nEND:VCALENDAR\n";
+    assert!(WorkCalendar::parse_holidays(malformed, "malformed.ics")?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_age() -> Result<(), Box<dyn std::error::Error>> {
+    let now: Date = Date::parse("2024-03-31")?;
+
+    assert_eq!(parse_age("0d", &now)?, 0);
+    assert_eq!(parse_age("90d", &now)?, 90);
+    assert_eq!(parse_age("2w", &now)?, 14);
+    assert_eq!(parse_age("1m", &now)?, 31);
+    assert_eq!(parse_age("1y", &now)?, 366);
+
+    for age in ["", "d", "90", "-3d", "3.5d", "today", "2024-01-01"] {
+        assert!(parse_age(age, &now).is_err(), "{}", age);
+    }
+
+    // Huge durations are rejected rather than overflowing
+    for age in [
+        "999999999y",
+        "3000000000m",
+        "99999999999999999999d",
+        "9223372036854775807w",
+    ] {
+        match parse_age(age, &now) {
+            Err(error) => assert!(
+                error.to_string().contains("duration is too large."),
+                "{}",
+                age
+            ),
+            Ok(days) => panic!("{}: {} days", age, days),
+        }
+    }
+    assert_eq!(parse_age("10000y", &now)?, 3652425);
+
+    Ok(())
+}
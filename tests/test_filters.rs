@@ -1,12 +1,30 @@
-use issue_tracking::filters::DateFilter;
+use issue_tracking::filters::And;
+use issue_tracking::filters::Filter;
 use issue_tracking::filters::Filters;
+use issue_tracking::filters::LabelExpr;
 use issue_tracking::filters::LabelFilter;
+use issue_tracking::filters::Not;
+use issue_tracking::filters::Or;
+use issue_tracking::filters::SearchFilter;
 use issue_tracking::filters::StateFilter;
 
 use issue_tracking::date::Date;
+use issue_tracking::date::Timestamp;
+use issue_tracking::date::WorkCalendar;
 use issue_tracking::input::Args;
+use issue_tracking::parser::parse_input;
+use issue_tracking::parser::DateField;
+use issue_tracking::parser::InputFormat;
 use issue_tracking::parser::Issue;
+use issue_tracking::parser::IssueState;
 use issue_tracking::parser::Label;
+use issue_tracking::parser::LabelPattern;
+use issue_tracking::parser::Milestone;
+use issue_tracking::parser::StateReason;
+use issue_tracking::parser::User;
+use issue_tracking::report::aging_report;
+use issue_tracking::report::AgingRow;
+use issue_tracking::timezone::TimeZone;
 
 /*
    Tests for filters.
@@ -16,22 +34,55 @@ use issue_tracking::parser::Label;
 fn test_build_filters() -> Result<(), Box<dyn std::error::Error>> {
     let args: Args = Args {
         output: String::from(""),
-        label: String::from("type:feature"),
-        state: String::from("opened"),
+        format: String::from("auto"),
+        sheets: None,
+        changelog: false,
+        changelog_sections: None,
+        columns: None,
+        label: vec![String::from("type:feature")],
+        state: String::from("open"),
         print_labels: false,
         start_date: String::from("(oldest)"),
         end_date: String::from("(newest)"),
-        json: String::from(""),
+        inputs: vec![],
         get: false,
+        verbose: false,
+        search: None,
+        search_regex: None,
+        search_snippet: false,
+        query: None,
+        author: vec![],
+        assignee: vec![],
+        milestone: vec![],
+        number: vec![],
+        ignore_case: false,
+        business_time: false,
+        weekend: String::from("sat,sun"),
+        working_hours: String::from("09:00-17:00"),
+        holidays: None,
+        timezone: String::from("UTC"),
+        date_field: String::from("updated"),
+        now: String::from("(today)"),
+        label_map: None,
+        min_comments: None,
+        min_reactions: None,
+        sort: None,
+        stale: None,
+        older_than: None,
+        younger_than: None,
+        aging_report: false,
+        list_format: String::from("text"),
+        list_sort: String::from("name"),
+        input_format: String::from("auto"),
     };
 
-    let filter: Filters = Filters::from_args(&args);
+    let filter: Filters = Filters::from_args(&args)?;
 
-    assert!(filter.label_filter.active);
-    assert_eq!(filter.label_filter.pattern, "type:feature");
-
-    assert!(filter.state_filter.active);
-    assert_eq!(filter.state_filter.pattern, "opened");
+    assert_eq!(filter.filters.len(), 2);
+    assert_eq!(
+        filter.describe(),
+        "labels match 'type:feature'\nstate is open"
+    );
 
     Ok(())
 }
@@ -40,78 +91,86 @@ fn test_build_filters() -> Result<(), Box<dyn std::error::Error>> {
 fn test_build_unused_filters() -> Result<(), Box<dyn std::error::Error>> {
     let args: Args = Args {
         output: String::from(""),
-        label: String::from("(all)"),
+        format: String::from("auto"),
+        sheets: None,
+        changelog: false,
+        changelog_sections: None,
+        columns: None,
+        label: vec![String::from("(all)")],
         state: String::from("(any)"),
         print_labels: false,
         start_date: String::from("(oldest)"),
         end_date: String::from("(newest)"),
-        json: String::from(""),
+        inputs: vec![],
         get: false,
+        verbose: false,
+        search: None,
+        search_regex: None,
+        search_snippet: false,
+        query: None,
+        author: vec![],
+        assignee: vec![],
+        milestone: vec![],
+        number: vec![],
+        ignore_case: false,
+        business_time: false,
+        weekend: String::from("sat,sun"),
+        working_hours: String::from("09:00-17:00"),
+        holidays: None,
+        timezone: String::from("UTC"),
+        date_field: String::from("updated"),
+        now: String::from("(today)"),
+        label_map: None,
+        min_comments: None,
+        min_reactions: None,
+        sort: None,
+        stale: None,
+        older_than: None,
+        younger_than: None,
+        aging_report: false,
+        list_format: String::from("text"),
+        list_sort: String::from("name"),
+        input_format: String::from("auto"),
     };
 
-    let filter: Filters = Filters::from_args(&args);
-
-    assert!(!filter.label_filter.active);
-    assert_eq!(filter.label_filter.pattern, "(all)");
+    let filter: Filters = Filters::from_args(&args)?;
 
-    assert!(!filter.state_filter.active);
-    assert_eq!(filter.state_filter.pattern, "(any)");
+    assert!(filter.is_empty());
+    assert!(filter.accept(&Issue::default()));
 
     Ok(())
 }
 
 #[test]
 fn test_label_filtering() -> Result<(), Box<dyn std::error::Error>> {
-    let start_date: Date = Date {
-        year: 2023,
-        month: 9,
-        day: 1,
-    };
-
-    let end_date: Date = Date {
-        year: 2023,
-        month: 9,
-        day: 1,
-    };
-
-    let filter: Filters = Filters {
-        label_filter: LabelFilter {
-            active: true,
-            pattern: String::from("type:feature"),
-        },
-
-        state_filter: StateFilter {
-            active: false,
-            pattern: String::from(""),
-        },
-
-        date_filter: DateFilter {
-            start_active: false,
-            start_date,
-            end_active: false,
-            end_date,
-        },
-    };
+    let filter: Filters = Filters::builder()
+        .labels(&[String::from("type:feature")])?
+        .build();
 
     let labels_a: Vec<Label> = vec![Label {
         name: String::from("type:feature"),
+        ..Default::default()
     }];
 
     let labels_b: Vec<Label> = vec![
         Label {
             name: String::from("rtl"),
+            ..Default::default()
         },
         Label {
             name: String::from("type:feature"),
+            ..Default::default()
         },
     ];
 
     let labels_c: Vec<Label> = vec![
         Label {
             name: String::from("others"),
+            ..Default::default()
         },
         Label {
             name: String::from("rtl"),
+            ..Default::default()
         },
     ];
 
@@ -119,44 +178,48 @@ fn test_label_filtering() -> Result<(), Box<dyn std::error::Error>> {
         title: String::from(""),
         number: 1,
         labels: labels_a,
-        state: String::from(""),
-        created_at: String::from("2020-06-15"),
-        updated_at: String::from("2020-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-15")?,
+        updated_at: Timestamp::parse("2020-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_b: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: labels_b,
-        state: String::from(""),
-        created_at: String::from("2020-06-15"),
-        updated_at: String::from("2020-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-15")?,
+        updated_at: Timestamp::parse("2020-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_c: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: labels_c,
-        state: String::from(""),
-        created_at: String::from("2020-06-15"),
-        updated_at: String::from("2020-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-15")?,
+        updated_at: Timestamp::parse("2020-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_d: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2020-06-15"),
-        updated_at: String::from("2020-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-15")?,
+        updated_at: Timestamp::parse("2020-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issues: [Issue; 4] = [issue_a, issue_b, issue_c, issue_d];
@@ -178,73 +241,56 @@ fn test_start_date_filtering() -> Result<(), Box<dyn std::error::Error>> {
         day: 15,
     };
 
-    let end_date: Date = Date {
-        year: 2023,
-        month: 8,
-        day: 12,
-    };
-
-    let filter: Filters = Filters {
-        label_filter: LabelFilter {
-            active: false,
-            pattern: String::from(""),
-        },
-
-        state_filter: StateFilter {
-            active: false,
-            pattern: String::from(""),
-        },
-
-        date_filter: DateFilter {
-            start_active: true,
-            start_date,
-            end_active: false,
-            end_date,
-        },
-    };
+    let filter: Filters = Filters::builder()
+        .dates(DateField::Updated, Some(start_date), None)
+        .build();
 
     let issue_a: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2020-06-16"),
-        updated_at: String::from("2020-06-16"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-16")?,
+        updated_at: Timestamp::parse("2020-06-16")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_b: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2022-06-15"),
-        updated_at: String::from("2022-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2022-06-15")?,
+        updated_at: Timestamp::parse("2022-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_c: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2020-06-11"),
-        updated_at: String::from("2020-06-11"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-11")?,
+        updated_at: Timestamp::parse("2020-06-11")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_d: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2012-08-14"),
-        updated_at: String::from("2012-08-14"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2012-08-14")?,
+        updated_at: Timestamp::parse("2012-08-14")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issues: [Issue; 4] = [issue_a, issue_b, issue_c, issue_d];
@@ -273,67 +319,56 @@ fn test_end_date_filtering() -> Result<(), Box<dyn std::error::Error>> {
         day: 12,
     };
 
-    let filter: Filters = Filters {
-        label_filter: LabelFilter {
-            active: false,
-            pattern: String::from(""),
-        },
-
-        state_filter: StateFilter {
-            active: false,
-            pattern: String::from(""),
-        },
-
-        date_filter: DateFilter {
-            start_active: true,
-            start_date,
-            end_active: true,
-            end_date,
-        },
-    };
+    let filter: Filters = Filters::builder()
+        .dates(DateField::Updated, Some(start_date), Some(end_date))
+        .build();
 
     let issue_a: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2020-06-16"),
-        updated_at: String::from("2020-06-16"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-16")?,
+        updated_at: Timestamp::parse("2020-06-16")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_b: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2022-06-15"),
-        updated_at: String::from("2022-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2022-06-15")?,
+        updated_at: Timestamp::parse("2022-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_c: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2024-06-15"),
-        updated_at: String::from("2024-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2024-06-15")?,
+        updated_at: Timestamp::parse("2024-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_d: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2023-08-14"),
-        updated_at: String::from("2023-08-14"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2023-08-14")?,
+        updated_at: Timestamp::parse("2023-08-14")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issues: [Issue; 4] = [issue_a, issue_b, issue_c, issue_d];
@@ -362,67 +397,56 @@ fn test_inbetween_date_filtering() -> Result<(), Box<dyn std::error::Error>> {
         day: 12,
     };
 
-    let filter: Filters = Filters {
-        label_filter: LabelFilter {
-            active: false,
-            pattern: String::from(""),
-        },
-
-        state_filter: StateFilter {
-            active: false,
-            pattern: String::from(""),
-        },
-
-        date_filter: DateFilter {
-            start_active: true,
-            start_date,
-            end_active: true,
-            end_date,
-        },
-    };
+    let filter: Filters = Filters::builder()
+        .dates(DateField::Updated, Some(start_date), Some(end_date))
+        .build();
 
     let issue_a: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2020-06-16"),
-        updated_at: String::from("2020-06-16"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-16")?,
+        updated_at: Timestamp::parse("2020-06-16")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_b: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2022-06-15"),
-        updated_at: String::from("2022-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2022-06-15")?,
+        updated_at: Timestamp::parse("2022-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_c: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2024-06-15"),
-        updated_at: String::from("2024-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2024-06-15")?,
+        updated_at: Timestamp::parse("2024-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_d: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from(""),
-        created_at: String::from("2023-08-14"),
-        updated_at: String::from("2023-08-14"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2023-08-14")?,
+        updated_at: Timestamp::parse("2023-08-14")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issues: [Issue; 4] = [issue_a, issue_b, issue_c, issue_d];
@@ -439,79 +463,54 @@ fn test_inbetween_date_filtering() -> Result<(), Box<dyn std::error::Error>> {
 
 #[test]
 fn test_state_filtering() -> Result<(), Box<dyn std::error::Error>> {
-    let start_date: Date = Date {
-        year: 2020,
-        month: 6,
-        day: 15,
-    };
-
-    let end_date: Date = Date {
-        year: 2023,
-        month: 8,
-        day: 12,
-    };
-
-    let filter: Filters = Filters {
-        label_filter: LabelFilter {
-            active: false,
-            pattern: String::from(""),
-        },
-
-        state_filter: StateFilter {
-            active: true,
-            pattern: String::from("opened"),
-        },
-
-        date_filter: DateFilter {
-            start_active: false,
-            start_date,
-            end_active: false,
-            end_date,
-        },
-    };
+    let filter: Filters = Filters::builder().state("open")?.build();
 
     let issue_a: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from("opened"),
-        created_at: String::from("2020-06-16"),
-        updated_at: String::from("2020-06-16"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2020-06-16")?,
+        updated_at: Timestamp::parse("2020-06-16")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_b: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from("opened"),
-        created_at: String::from("2022-06-15"),
-        updated_at: String::from("2022-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Open,
+        created_at: Timestamp::parse("2022-06-15")?,
+        updated_at: Timestamp::parse("2022-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_c: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from("closed"),
-        created_at: String::from("2024-06-15"),
-        updated_at: String::from("2024-06-15"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Closed,
+        created_at: Timestamp::parse("2024-06-15")?,
+        updated_at: Timestamp::parse("2024-06-15")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issue_d: Issue = Issue {
         title: String::from(""),
         number: 1,
         labels: Vec::new(),
-        state: String::from("closed"),
-        created_at: String::from("2023-08-14"),
-        updated_at: String::from("2023-08-14"),
-        closed_at: Some(String::from("")),
+        state: IssueState::Closed,
+        created_at: Timestamp::parse("2023-08-14")?,
+        updated_at: Timestamp::parse("2023-08-14")?,
+        closed_at: None,
         draft: None,
+        ..Default::default()
     };
 
     let issues: [Issue; 4] = [issue_a, issue_b, issue_c, issue_d];
@@ -525,3 +524,676 @@ fn test_state_filtering() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_date_field_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    // Issues created before March 2023 and closed after 2023-01-01
+    let filter: Filters = Filters::builder()
+        .dates(DateField::Closed, Some(Date::parse("2023-01-01")?), None)
+        .dates(DateField::Created, None, Some(Date::parse("2023-02-28")?))
+        .build();
+
+    let issue_a: Issue = Issue {
+        created_at: Timestamp::parse("2022-11-02T10:00:00Z")?,
+        updated_at: Timestamp::parse("2023-06-16T10:00:00Z")?,
+        closed_at: Some(Timestamp::parse("2023-01-05T10:00:00Z")?),
+        ..Default::default()
+    };
+
+    let issue_b: Issue = Issue {
+        created_at: Timestamp::parse("2022-11-02T10:00:00Z")?,
+        updated_at: Timestamp::parse("2023-06-16T10:00:00Z")?,
+        closed_at: Some(Timestamp::parse("2022-12-31T10:00:00Z")?),
+        ..Default::default()
+    };
+
+    let issue_c: Issue = Issue {
+        created_at: Timestamp::parse("2023-03-01T10:00:00Z")?,
+        updated_at: Timestamp::parse("2023-03-01T10:00:00Z")?,
+        closed_at: Some(Timestamp::parse("2023-03-02T10:00:00Z")?),
+        ..Default::default()
+    };
+
+    // Issues that are not closed cannot match a closing date
+    let issue_d: Issue = Issue {
+        created_at: Timestamp::parse("2022-11-02T10:00:00Z")?,
+        updated_at: Timestamp::parse("2023-06-16T10:00:00Z")?,
+        closed_at: None,
+        ..Default::default()
+    };
+
+    let issues: [Issue; 4] = [issue_a, issue_b, issue_c, issue_d];
+
+    let expected: [bool; 4] = [false, true, true, true];
+
+    for n in 0..4 {
+        assert_eq!(filter.reject(&issues[n]), expected[n]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_label_expressions() -> Result<(), Box<dyn std::error::Error>> {
+    let labeled = |names: &[&str]| Issue {
+        labels: names
+            .iter()
+            .map(|name| Label {
+                name: String::from(*name),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let issues: [Issue; 4] = [
+        labeled(&["bug"]),
+        labeled(&["bug", "wontfix"]),
+        labeled(&["frontend", "feature"]),
+        labeled(&["backend"]),
+    ];
+
+    // Expressions and the issues they reject
+    let cases: [(Vec<&str>, [bool; 4]); 5] = [
+        (vec!["bug,!wontfix"], [false, true, true, true]),
+        (vec!["frontend|backend"], [true, true, false, false]),
+        (vec!["bug", "!wontfix"], [false, true, true, true]),
+        (
+            vec!["frontend|backend", "!feature"],
+            [true, true, true, false],
+        ),
+        (vec!["!bug"], [true, true, false, false]),
+    ];
+
+    for (labels, expected) in cases {
+        let labels: Vec<String> = labels.iter().map(|label| String::from(*label)).collect();
+        let filter: LabelFilter = LabelFilter::new(&labels, false)?;
+
+        for n in 0..4 {
+            assert_eq!(filter.reject(&issues[n]), expected[n]);
+        }
+    }
+
+    // The parsed expression shows the precedence of the operators
+    let expression: LabelExpr = LabelExpr::parse("frontend | backend, !wontfix", false)?;
+    assert_eq!(
+        expression.to_string(),
+        "('frontend' OR 'backend') AND NOT 'wontfix'"
+    );
+
+    assert!(LabelExpr::parse("bug,,wontfix", false).is_err());
+    assert!(LabelExpr::parse("bug|!", false).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_label_patterns() -> Result<(), Box<dyn std::error::Error>> {
+    let labeled = |names: &[&str]| Issue {
+        labels: names
+            .iter()
+            .map(|name| Label {
+                name: String::from(*name),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let issues: [Issue; 4] = [
+        labeled(&["area:cpu", "prio:high"]),
+        labeled(&["area:mmu", "prio:low"]),
+        labeled(&["Prio:Critical"]),
+        labeled(&["bug"]),
+    ];
+
+    // Expressions, case sensitivity and the issues they reject
+    let cases: [(&str, bool, [bool; 4]); 6] = [
+        ("area:*", false, [false, false, true, true]),
+        ("area:*,!area:mmu", false, [false, true, true, true]),
+        ("/^prio:(high|critical)$/", false, [false, true, true, true]),
+        ("/^prio:(high|critical)$/", true, [false, true, false, true]),
+        ("prio:*|BUG", true, [false, false, false, false]),
+        ("prio:*|BUG", false, [false, false, true, true]),
+    ];
+
+    for (expression, ignore_case, expected) in cases {
+        let filter: LabelFilter = LabelFilter::new(&[String::from(expression)], ignore_case)?;
+
+        for n in 0..4 {
+            assert_eq!(filter.reject(&issues[n]), expected[n]);
+        }
+    }
+
+    // Separators inside a regex are not operators
+    assert_eq!(
+        LabelExpr::parse("/^a(b|c)$/,!/x,y/", false)?.to_string(),
+        "/^a(b|c)$/ AND NOT /x,y/"
+    );
+
+    assert!(LabelPattern::parse("/^prio:(high/", false).is_err());
+    assert!(LabelPattern::parse("/unterminated", false).is_err());
+
+    // Label listings keep the labels matching the expression on their own
+    let filter: LabelFilter = LabelFilter::new(&[String::from("prio:*,!prio:low")], false)?;
+    assert!(filter.accept_label("prio:high"));
+    assert!(!filter.accept_label("prio:low"));
+    assert!(!filter.accept_label("area:cpu"));
+
+    Ok(())
+}
+
+#[test]
+fn test_search_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    let repository = parse_input("tests/doc/gh-cli-export.json", &InputFormat::Auto)?;
+    let issues: &[Issue] = &repository.issues;
+
+    // Title, body and comments are searched, ignoring case
+    let cases: [(Option<&str>, Option<&str>, [bool; 2]); 5] = [
+        (Some("crash"), None, [false, true]),
+        (Some("PROPER ERROR"), None, [false, true]),
+        (Some("segmentation fault"), None, [false, true]),
+        (None, Some(r"csv (columns|rows)"), [true, false]),
+        (Some("workaround"), Some("^Document"), [true, true]),
+    ];
+
+    for (text, regex, expected) in cases {
+        let filter: SearchFilter = SearchFilter::new(text, regex)?;
+
+        for n in 0..2 {
+            assert_eq!(filter.reject(&issues[n]), expected[n]);
+        }
+    }
+
+    // Snippets highlight the match with some context on a single line
+    let filter: SearchFilter = SearchFilter::new(Some("PROPER"), None)?;
+    assert_eq!(
+        filter.find(&issues[0]),
+        Some(String::from(
+            "...ectory should be created or a **proper** error displayed."
+        ))
+    );
+
+    assert!(SearchFilter::new(None, Some("(unclosed")).is_err());
+    // Texts too large to be compiled are reported rather than matching everything
+    let huge: String = "x".repeat(300_000);
+    assert!(SearchFilter::new(Some(&huge), None).is_err());
+    assert!(Filters::builder().search(&huge).is_err());
+
+    Ok(())
+}
+
+// User-defined filter on the issue number
+struct IsNumber(i32);
+
+impl Filter for IsNumber {
+    fn accept(&self, issue: &Issue) -> bool {
+        issue.number == self.0
+    }
+
+    fn describe(&self) -> String {
+        format!("number is {}", self.0)
+    }
+}
+
+#[test]
+fn test_filter_combinators() -> Result<(), Box<dyn std::error::Error>> {
+    let issue = |number: i32, state: IssueState, label: &str| Issue {
+        number,
+        state,
+        labels: vec![Label {
+            name: String::from(label),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let issues: [Issue; 4] = [
+        issue(1, IssueState::Open, "bug"),
+        issue(2, IssueState::Open, "bug"),
+        issue(3, IssueState::Closed, "bug"),
+        issue(4, IssueState::Closed, "feature"),
+    ];
+
+    // Bugs that are either issue #1 or not open
+    let filter: Filters = Filters::builder()
+        .labels(&[String::from("bug")])?
+        .filter(Or(vec![
+            Box::new(IsNumber(1)),
+            Box::new(Not(Box::new(StateFilter::parse("open")?))),
+        ]))
+        .build();
+
+    let expected: [bool; 4] = [false, true, false, true];
+
+    for n in 0..4 {
+        assert_eq!(filter.reject(&issues[n]), expected[n]);
+    }
+
+    assert_eq!(
+        filter.describe(),
+        "labels match 'bug'\n(number is 1) OR (NOT (state is open))"
+    );
+
+    // Empty conjunctions accept any issue, empty disjunctions none
+    assert!(And(vec![]).accept(&issues[0]));
+    assert!(Or(vec![]).reject(&issues[0]));
+
+    let filter = And(vec![Box::new(IsNumber(3)), Box::new(IsNumber(4))]);
+    assert!(issues.iter().all(|issue| filter.reject(issue)));
+
+    Ok(())
+}
+
+#[test]
+fn test_state_reason_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    let issue = |state: IssueState, state_reason: Option<StateReason>| Issue {
+        state,
+        state_reason,
+        ..Default::default()
+    };
+
+    let issues: [Issue; 4] = [
+        issue(IssueState::Open, None),
+        issue(IssueState::Open, Some(StateReason::Reopened)),
+        issue(IssueState::Closed, Some(StateReason::Completed)),
+        issue(IssueState::Closed, Some(StateReason::NotPlanned)),
+    ];
+
+    // States and the issues they reject
+    let cases: [(&str, [bool; 4]); 5] = [
+        ("open", [false, false, true, true]),
+        ("open:reopened", [true, false, true, true]),
+        ("closed", [true, true, false, false]),
+        ("closed:completed", [true, true, false, true]),
+        ("CLOSED:not planned", [true, true, true, false]),
+    ];
+
+    for (state, expected) in cases {
+        let filter: StateFilter = StateFilter::parse(state)?;
+
+        for n in 0..4 {
+            assert_eq!(filter.reject(&issues[n]), expected[n], "{}", state);
+        }
+    }
+
+    // Unknown states are rejected with a suggestion
+    let error = StateFilter::parse("opened").err().map(|error| error.msg);
+    assert!(error.is_some_and(|msg| msg.ends_with("Did you mean 'open'?")));
+
+    let error = StateFilter::parse("closed:not_plan")
+        .err()
+        .map(|error| error.msg);
+    assert!(error.is_some_and(|msg| msg.ends_with("Did you mean 'closed:not_planned'?")));
+
+    assert!(StateFilter::parse("open:completed").is_err());
+    assert!(StateFilter::parse("merged").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_people_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    let user = |login: &str| User {
+        login: String::from(login),
+    };
+    let milestone = |title: &str| Milestone {
+        title: String::from(title),
+    };
+
+    let issues: [Issue; 4] = [
+        Issue {
+            number: 99,
+            user: Some(user("alice")),
+            assignees: vec![user("bob"), user("carol")],
+            milestone: Some(milestone("v1.0")),
+            ..Default::default()
+        },
+        Issue {
+            number: 100,
+            user: Some(user("bob")),
+            assignees: vec![user("alice")],
+            ..Default::default()
+        },
+        Issue {
+            number: 250,
+            user: Some(user("Carol")),
+            milestone: Some(milestone("v2.0")),
+            ..Default::default()
+        },
+        Issue {
+            number: 251,
+            user: None,
+            ..Default::default()
+        },
+    ];
+
+    let values =
+        |values: &[&str]| -> Vec<String> { values.iter().map(|v| String::from(*v)).collect() };
+
+    // Filters and the issues they reject
+    let cases: [(Filters, [bool; 4]); 7] = [
+        (
+            Filters::builder()
+                .authors(&values(&["alice", "carol"]))
+                .build(),
+            [false, true, false, true],
+        ),
+        (
+            Filters::builder().assignees(&values(&["carol"])).build(),
+            [false, true, true, true],
+        ),
+        (
+            Filters::builder()
+                .assignees(&values(&["none", "alice"]))
+                .build(),
+            [true, false, false, false],
+        ),
+        (
+            Filters::builder()
+                .milestones(&values(&["v1.0", "v2.0"]))
+                .build(),
+            [false, true, false, true],
+        ),
+        (
+            Filters::builder().milestones(&values(&["none"])).build(),
+            [true, false, true, false],
+        ),
+        (
+            Filters::builder().numbers(&values(&["100..250"]))?.build(),
+            [true, false, false, true],
+        ),
+        (
+            Filters::builder()
+                .numbers(&values(&["<100", ">=251"]))?
+                .authors(&values(&["alice"]))
+                .build(),
+            [false, true, true, true],
+        ),
+    ];
+
+    for (filter, expected) in cases {
+        for n in 0..4 {
+            assert_eq!(
+                filter.reject(&issues[n]),
+                expected[n],
+                "{}",
+                filter.describe()
+            );
+        }
+    }
+
+    assert!(Filters::builder().numbers(&values(&["100..abc"])).is_err());
+    assert!(Filters::builder()
+        .numbers(&values(&[">9223372036854775807"]))
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_age_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    let issue = |created: &str, updated: &str| -> Result<Issue, Box<dyn std::error::Error>> {
+        Ok(Issue {
+            created_at: Timestamp::parse(created)?,
+            updated_at: Timestamp::parse(updated)?,
+            ..Default::default()
+        })
+    };
+
+    let now: Date = Date::parse("2024-04-30")?;
+    let issues: [Issue; 3] = [
+        issue("2023-12-01", "2024-01-31")?,
+        issue("2024-03-31", "2024-04-20")?,
+        issue("2024-04-25", "2024-04-25")?,
+    ];
+
+    // Filters and the issues they reject
+    let cases: [(Filters, [bool; 3]); 5] = [
+        (
+            Filters::builder().now(now).stale("90d")?.build(),
+            [false, true, true],
+        ),
+        (
+            Filters::builder().now(now).stale("91d")?.build(),
+            [true, true, true],
+        ),
+        (
+            Filters::builder().now(now).older_than("30d")?.build(),
+            [false, true, true],
+        ),
+        (
+            Filters::builder().now(now).younger_than("1m")?.build(),
+            [true, false, false],
+        ),
+        (
+            Filters::builder()
+                .now(now)
+                .older_than("1w")?
+                .younger_than("1y")?
+                .build(),
+            [false, false, true],
+        ),
+    ];
+
+    for (filter, expected) in cases {
+        for n in 0..3 {
+            assert_eq!(
+                filter.reject(&issues[n]),
+                expected[n],
+                "{}",
+                filter.describe()
+            );
+        }
+    }
+
+    assert_eq!(
+        Filters::builder().now(now).stale("90d")?.build().describe(),
+        "updated >=90 days ago"
+    );
+
+    // Weekends and holidays are not counted in working days
+    let mut calendar: WorkCalendar = WorkCalendar::default();
+    calendar.holidays.insert(Date::parse("2024-04-29")?);
+
+    let updated: [Issue; 2] = [
+        issue("2024-04-23", "2024-04-23")?,
+        issue("2024-04-22", "2024-04-22")?,
+    ];
+    let calendar_cases: [(Filters, [bool; 2]); 3] = [
+        (
+            Filters::builder().now(now).stale("5d")?.build(),
+            [false, false],
+        ),
+        (
+            Filters::builder()
+                .now(now)
+                .calendar(calendar.clone())
+                .stale("5d")?
+                .build(),
+            [true, false],
+        ),
+        (
+            Filters::builder()
+                .now(now)
+                .calendar(calendar.clone())
+                .stale("1w")?
+                .build(),
+            [false, false],
+        ),
+    ];
+
+    for (filter, expected) in calendar_cases {
+        for n in 0..2 {
+            assert_eq!(
+                filter.reject(&updated[n]),
+                expected[n],
+                "{}",
+                filter.describe()
+            );
+        }
+    }
+
+    assert_eq!(
+        Filters::builder()
+            .now(now)
+            .calendar(calendar)
+            .stale("5d")?
+            .build()
+            .describe(),
+        "updated >=5 working days ago"
+    );
+    assert!(Filters::builder().stale("90").is_err());
+    assert!(Filters::builder().older_than("d").is_err());
+    assert!(Filters::builder().younger_than("-3d").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_aging_report() -> Result<(), Box<dyn std::error::Error>> {
+    let issue = |created: &str, labels: &[&str], state: IssueState| -> Issue {
+        Issue {
+            created_at: Timestamp::parse(created).unwrap(),
+            labels: labels
+                .iter()
+                .map(|name| Label {
+                    name: String::from(*name),
+                    ..Default::default()
+                })
+                .collect(),
+            state,
+            ..Default::default()
+        }
+    };
+
+    let issues: [Issue; 6] = [
+        issue("2024-04-23", &["bug"], IssueState::Open),
+        issue("2024-04-22", &["bug", "ui"], IssueState::Open),
+        issue("2024-03-31", &["bug"], IssueState::Open),
+        issue("2024-01-31", &["ui"], IssueState::Open),
+        issue("2024-01-30", &[], IssueState::Open),
+        issue("2023-01-01", &["bug"], IssueState::Closed),
+    ];
+
+    let row = |label: &str, counts: [usize; 4]| AgingRow {
+        label: String::from(label),
+        week: counts[0],
+        month: counts[1],
+        quarter: counts[2],
+        older: counts[3],
+        total: counts.iter().sum(),
+    };
+
+    let now: Date = Date::parse("2024-04-30")?;
+    let report = aging_report(
+        &issues,
+        &Filters::builder().build(),
+        &now,
+        &TimeZone::utc(),
+        None,
+    );
+
+    assert_eq!(
+        report,
+        vec![
+            row("(no label)", [0, 0, 0, 1]),
+            row("bug", [1, 2, 0, 0]),
+            row("ui", [0, 1, 1, 0]),
+            row("Total", [1, 2, 1, 1]),
+        ]
+    );
+
+    // Only the issues accepted by the filters are counted
+    let filters: Filters = Filters::builder().labels(&[String::from("ui")])?.build();
+    let report = aging_report(&issues, &filters, &now, &TimeZone::utc(), None);
+
+    assert_eq!(
+        report,
+        vec![
+            row("bug", [0, 1, 0, 0]),
+            row("ui", [0, 1, 1, 0]),
+            row("Total", [0, 1, 1, 0]),
+        ]
+    );
+
+    // With a calendar, the bands count working days
+    let calendar: WorkCalendar = WorkCalendar::new("sat,sun", "09:00-17:00", None)?;
+    let report = aging_report(
+        &issues,
+        &Filters::builder().build(),
+        &now,
+        &TimeZone::utc(),
+        Some(&calendar),
+    );
+
+    assert_eq!(
+        report,
+        vec![
+            row("(no label)", [0, 0, 1, 0]),
+            row("bug", [2, 1, 0, 0]),
+            row("ui", [1, 0, 1, 0]),
+            row("Total", [2, 1, 2, 0]),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_engagement_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    let issues = parse_input("tests/doc/reactions.jsonl", &InputFormat::Auto)?.issues;
+
+    // Filters and the issues they reject
+    let cases: [(Filters, [bool; 3]); 5] = [
+        (
+            Filters::builder().min_comments(3).build(),
+            [false, false, true],
+        ),
+        (
+            Filters::builder().min_reactions("10")?.build(),
+            [false, true, true],
+        ),
+        (
+            Filters::builder().min_reactions("heart:1")?.build(),
+            [false, false, true],
+        ),
+        (
+            Filters::builder()
+                .min_reactions("+1:10")?
+                .min_comments(5)
+                .build(),
+            [true, true, true],
+        ),
+        (
+            Filters::builder().min_reactions("0")?.build(),
+            [false, false, false],
+        ),
+    ];
+
+    for (filter, expected) in cases {
+        for n in 0..3 {
+            assert_eq!(
+                filter.reject(&issues[n]),
+                expected[n],
+                "{}",
+                filter.describe()
+            );
+        }
+    }
+
+    assert_eq!(
+        Filters::builder()
+            .min_reactions("+1:10")?
+            .build()
+            .describe(),
+        "at least 10 '+1' reactions"
+    );
+    assert!(Filters::builder().min_reactions("ten").is_err());
+    assert!(Filters::builder()
+        .min_reactions("hearts:2")
+        .err()
+        .is_some_and(|e| e.to_string().contains("Did you mean 'heart'?")));
+
+    Ok(())
+}
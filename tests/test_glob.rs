@@ -0,0 +1,48 @@
+use issue_tracking::glob::expand;
+use issue_tracking::glob::is_pattern;
+use issue_tracking::glob::matches;
+
+/*
+   Tests for the glob expansion of input patterns.
+*/
+
+#[test]
+fn test_glob_matches() -> Result<(), Box<dyn std::error::Error>> {
+    let cases: [(&str, &str, bool); 8] = [
+        ("*.json", "issues.json", true),
+        ("*.json", "issues.csv", false),
+        ("issues-?.json", "issues-1.json", true),
+        ("issues-?.json", "issues-12.json", false),
+        ("issues-[0-9].json", "issues-7.json", true),
+        ("issues-[!0-9].json", "issues-7.json", false),
+        ("a*b*c", "aXXbYYc", true),
+        ("plain", "plain", true),
+    ];
+
+    for (pattern, text, expected) in cases {
+        assert_eq!(matches(pattern, text), expected, "{} ~ {}", pattern, text);
+    }
+
+    assert!(is_pattern("tests/*.json"));
+    assert!(!is_pattern("tests/doc/bogus.json"));
+
+    Ok(())
+}
+
+#[test]
+fn test_glob_expand() -> Result<(), Box<dyn std::error::Error>> {
+    let paths: Vec<String> = expand("tests/d?c/*issue*.json")?;
+
+    assert_eq!(
+        paths,
+        vec![
+            "tests/doc/openhwgroup-cva6_issues.json",
+            "tests/doc/riscv-riscv-cfi_issues.json",
+            "tests/doc/teobiton-issue-tracking.json",
+        ]
+    );
+
+    assert!(expand("tests/doc/*.nothing").is_err());
+
+    Ok(())
+}
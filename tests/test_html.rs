@@ -0,0 +1,167 @@
+use issue_tracking::columns::Column;
+use issue_tracking::date::Date;
+use issue_tracking::date::Timestamp;
+use issue_tracking::filters::Filters;
+use issue_tracking::html::escape;
+use issue_tracking::html::html_report;
+use issue_tracking::html::label_colors;
+use issue_tracking::html::timeline;
+use issue_tracking::html::TimelinePoint;
+use issue_tracking::parser::Issue;
+use issue_tracking::parser::IssueState;
+use issue_tracking::parser::Label;
+use issue_tracking::timezone::TimeZone;
+use issue_tracking::writer::ExportSettings;
+
+/*
+   Tests for the HTML report.
+*/
+
+fn export_settings(now: &str, columns: &str) -> Result<ExportSettings, Box<dyn std::error::Error>> {
+    Ok(ExportSettings {
+        timezone: TimeZone::utc(),
+        now: Timestamp::parse(now)?,
+        calendar: None,
+        columns: Column::parse_list(columns)?,
+        sort: None,
+        sheets: None,
+        changelog: None,
+    })
+}
+
+fn issue(
+    number: i32,
+    created: &str,
+    closed: Option<&str>,
+) -> Result<Issue, Box<dyn std::error::Error>> {
+    Ok(Issue {
+        number,
+        title: format!("Issue {}", number),
+        created_at: Timestamp::parse(created)?,
+        closed_at: closed.map(Timestamp::parse).transpose()?,
+        state: match closed {
+            Some(_) => IssueState::Closed,
+            None => IssueState::Open,
+        },
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_html_helpers() {
+    assert_eq!(
+        escape("<b>\"Tom\" & 'Jerry'</b>"),
+        "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;"
+    );
+
+    // Dark text on light labels, white text on dark ones
+    assert_eq!(
+        label_colors(Some("FBCA04")),
+        (String::from("#fbca04"), "#1f2328")
+    );
+    assert_eq!(
+        label_colors(Some("0e8a16")),
+        (String::from("#0e8a16"), "#ffffff")
+    );
+    assert_eq!(
+        label_colors(Some("red")),
+        (String::from("#ededed"), "#1f2328")
+    );
+    assert_eq!(label_colors(None), (String::from("#ededed"), "#1f2328"));
+}
+
+#[test]
+fn test_timeline() -> Result<(), Box<dyn std::error::Error>> {
+    let settings: ExportSettings = export_settings("2023-06-05", "id")?;
+    let issues: [Issue; 3] = [
+        issue(1, "2023-06-01T10:00:00Z", Some("2023-06-03T10:00:00Z"))?,
+        issue(2, "2023-06-02T10:00:00Z", None)?,
+        issue(3, "2023-06-04T10:00:00Z", None)?,
+    ];
+
+    let point = |date: &str, open: usize, closed: usize| TimelinePoint {
+        date: Date::parse(date).unwrap(),
+        open,
+        closed,
+    };
+
+    assert_eq!(
+        timeline(&issues, &settings),
+        vec![
+            point("2023-06-01", 1, 0),
+            point("2023-06-02", 2, 0),
+            point("2023-06-03", 1, 1),
+            point("2023-06-04", 2, 1),
+            point("2023-06-05", 2, 1),
+        ]
+    );
+
+    // Long periods are sampled, always ending on the reference date
+    let long: Vec<TimelinePoint> = timeline(&issues, &export_settings("2024-06-05", "id")?);
+    assert!(long.len() <= 121);
+    assert_eq!(
+        long.last().map(|point| point.date),
+        Some(Date::parse("2024-06-05")?)
+    );
+
+    assert!(timeline(&[], &settings).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_html_report() -> Result<(), Box<dyn std::error::Error>> {
+    let settings: ExportSettings = export_settings("2023-06-05", "id,title,labels,created")?;
+
+    let mut labeled: Issue = issue(7, "2023-06-01T10:00:00Z", None)?;
+    labeled.title = String::from("Crash <on> start");
+    labeled.repository_url = Some(String::from("https://api.github.com/repos/owner/repo"));
+    labeled.labels = vec![Label {
+        name: String::from("bug"),
+        color: Some(String::from("d73a4a")),
+        description: None,
+    }];
+
+    let issues: [Issue; 2] = [
+        labeled,
+        issue(8, "2023-06-02T10:00:00Z", Some("2023-06-04T10:00:00Z"))?,
+    ];
+    let report: String = html_report(&issues, &Filters::builder().build(), &settings);
+
+    assert!(report.starts_with("<!DOCTYPE html>"));
+    assert!(report.ends_with("</html>\n"));
+    // Nothing is loaded from elsewhere
+    assert!(!report.contains("src="));
+    assert!(!report.contains("<link"));
+
+    assert!(report.contains("<th>ID</th><th>Title</th><th>Labels</th><th>Created at</th>"));
+    assert!(report.contains(
+        r#"<td data-value="7"><a href="https://github.com/owner/repo/issues/7">#7</a></td>"#
+    ));
+    assert!(report.contains("Crash &lt;on&gt; start"));
+    assert!(report.contains(
+        r#"<span class="label" style="background-color: #d73a4a; color: #ffffff">bug</span>"#
+    ));
+    assert!(report.contains(r#"<td data-value="2023-06-02">2023-06-02</td>"#));
+
+    // Only http(s) pages are linked, other URLs are written as text
+    let mut scripted: Issue = issue(9, "2023-06-03T10:00:00Z", None)?;
+    scripted.html_url = Some(String::from("javascript:alert(1)"));
+    let unlinked: String = html_report(
+        &[scripted],
+        &Filters::builder().build(),
+        &export_settings("2023-06-05", "id,url")?,
+    );
+
+    assert!(!unlinked.contains("href=\"javascript:"));
+    assert!(unlinked.contains(r#"<td data-value="9">9</td>"#));
+    assert!(unlinked.contains(r#"<td data-value="javascript:alert(1)">javascript:alert(1)</td>"#));
+
+    // Summary counts and charts
+    assert!(report.contains(r#"<div class="card open"><div class="count">1</div>open</div>"#));
+    assert!(report.contains(r#"<div class="card closed"><div class="count">1</div>closed</div>"#));
+    assert_eq!(report.matches("<svg").count(), 2);
+    assert_eq!(report.matches("<polyline").count(), 2);
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use issue_tracking::columns::Column;
+use issue_tracking::date::Timestamp;
+use issue_tracking::filters::Filters;
+use issue_tracking::markdown::escape;
+use issue_tracking::markdown::markdown_table;
+use issue_tracking::markdown::Changelog;
+use issue_tracking::parser::Issue;
+use issue_tracking::parser::IssueState;
+use issue_tracking::parser::Label;
+use issue_tracking::parser::StateReason;
+use issue_tracking::timezone::TimeZone;
+use issue_tracking::writer::ExportSettings;
+
+/*
+   Tests for Markdown tables and changelogs.
+*/
+
+fn issue(number: i32, title: &str, labels: &[&str], reason: Option<StateReason>) -> Issue {
+    Issue {
+        number,
+        title: String::from(title),
+        repository_url: Some(String::from("https://api.github.com/repos/owner/repo")),
+        labels: labels
+            .iter()
+            .map(|name| Label {
+                name: String::from(*name),
+                ..Default::default()
+            })
+            .collect(),
+        state: match reason {
+            Some(StateReason::Reopened) | None => IssueState::Open,
+            Some(_) => IssueState::Closed,
+        },
+        state_reason: reason,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_markdown_table() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(escape("a | b\n c"), "a \\| b c");
+
+    let settings: ExportSettings = ExportSettings {
+        timezone: TimeZone::utc(),
+        now: Timestamp::parse("2023-06-30")?,
+        calendar: None,
+        columns: Column::parse_list("id,title=Summary,labels")?,
+        sort: None,
+        sheets: None,
+        changelog: None,
+    };
+
+    let mut unlinked: Issue = issue(2, "Pipes | in titles", &[], None);
+    unlinked.repository_url = None;
+
+    // Only http(s) pages are linked
+    let mut scripted: Issue = issue(3, "Script", &[], None);
+    scripted.html_url = Some(String::from("javascript:alert(1)"));
+
+    let issues: [Issue; 3] = [issue(1, "Crash", &["bug", "ui"], None), unlinked, scripted];
+    let table: String = markdown_table(&issues, &Filters::builder().build(), &settings);
+
+    assert_eq!(
+        table,
+        "| ID | Summary | Labels |\n\
+        | --- | --- | --- |\n\
+        | [#1](https://github.com/owner/repo/issues/1) | Crash | bug; ui |\n\
+        | #2 | Pipes \\| in titles |  |\n\
+        | #3 | Script |  |\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_changelog() -> Result<(), Box<dyn std::error::Error>> {
+    let completed = Some(StateReason::Completed);
+    let issues: [Issue; 6] = [
+        issue(1, "Export to Excel", &["enhancement"], completed),
+        issue(2, "Crash on empty labels", &["Bug", "ui"], completed),
+        issue(3, "Still open", &["bug"], None),
+        issue(4, "Won't do", &["feature"], Some(StateReason::NotPlanned)),
+        issue(5, "Bump dependencies", &[], completed),
+        issue(6, "Dark theme", &["feature"], completed),
+    ];
+
+    // Default sections, matching labels regardless of case
+    assert_eq!(
+        Changelog::default().render(&issues),
+        "## Features\n\n\
+        - Export to Excel ([#1](https://github.com/owner/repo/issues/1))\n\
+        - Dark theme ([#6](https://github.com/owner/repo/issues/6))\n\
+        \n\
+        ## Bug fixes\n\n\
+        - Crash on empty labels ([#2](https://github.com/owner/repo/issues/2))\n\
+        \n\
+        ## Other changes\n\n\
+        - Bump dependencies ([#5](https://github.com/owner/repo/issues/5))\n"
+    );
+
+    // Sections from a file, in order, with globs and a custom last section
+    let changelog: Changelog = Changelog::from_json(
+        r#"{
+            "sections": [
+                {"title": "Interface", "labels": ["ui*"]},
+                {"title": "New", "labels": ["/^(feature|enhancement)$/"]}
+            ],
+            "other": "Misc"
+        }"#,
+        "sections.json",
+    )?;
+
+    assert_eq!(changelog.section(&issues[1]), "Interface");
+    assert_eq!(changelog.section(&issues[5]), "New");
+    assert_eq!(changelog.section(&issues[4]), "Misc");
+    assert!(changelog
+        .render(&issues)
+        .starts_with("## Interface\n\n- Crash"));
+
+    assert!(Changelog::from_json(r#"{"sections": [{"title": "A"}]}"#, "sections.json").is_err());
+    assert!(Changelog::from_json(
+        r#"{"sections": [{"title": "A", "labels": ["/(/"]}]}"#,
+        "sections.json"
+    )
+    .is_err());
+
+    Ok(())
+}
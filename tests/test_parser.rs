@@ -1,7 +1,20 @@
 use std::path::Path;
 
+use issue_tracking::date::Timestamp;
+use issue_tracking::parser::collect_repo_labels;
+use issue_tracking::parser::merge_repositories;
+use issue_tracking::parser::parse_input;
 use issue_tracking::parser::parse_json_input;
+use issue_tracking::parser::InputFormat;
+use issue_tracking::parser::Issue;
+use issue_tracking::parser::IssueSort;
+use issue_tracking::parser::IssueState;
+use issue_tracking::parser::Label;
+use issue_tracking::parser::LabelMap;
+use issue_tracking::parser::LabelStats;
 use issue_tracking::parser::Repository;
+use issue_tracking::parser::StateReason;
+use issue_tracking::reader::parse_csv;
 
 /*
    Tests for the parse_correct_json function.
@@ -23,7 +36,7 @@ fn test_parse_correct_json() -> Result<(), Box<dyn std::error::Error>> {
         repository.issues[0].title,
         "SIM_ROOT requires setting prior to make"
     );
-    assert_eq!(repository.issues[53].state, "closed");
+    assert_eq!(repository.issues[53].state, IssueState::Closed);
 
     Ok(())
 }
@@ -38,3 +51,273 @@ fn test_parse_wrong_json() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_merge_repositories() -> Result<(), Box<dyn std::error::Error>> {
+    let json_file = Path::new("tests/doc/teobiton-issue-tracking.json");
+
+    let first: Repository = parse_json_input(json_file)?;
+    let mut second: Repository = parse_json_input(json_file)?;
+
+    // Make the second copy of the first issue more recent than the original
+    second.issues[0].updated_at = Timestamp::parse("2023-08-01T10:00:00Z")?;
+    second.issues[0].title = String::from("Updated title");
+
+    let (repository, duplicates) = merge_repositories(vec![first, second]);
+
+    assert_eq!(duplicates, 3);
+    assert_eq!(repository.issues.len(), 3);
+    assert_eq!(repository.issues[0].title, "Updated title");
+    assert_eq!(repository.issues[1].title, "Improve error management");
+
+    // Without repository URL, the repository is taken from the web page of the issues
+    let issue = |number: i32, html_url: Option<&str>| Issue {
+        number,
+        html_url: html_url.map(String::from),
+        ..Default::default()
+    };
+
+    let (repository, duplicates) = merge_repositories(vec![
+        Repository {
+            issues: vec![
+                issue(12, Some("https://github.com/owner/first/issues/12")),
+                issue(13, None),
+            ],
+        },
+        Repository {
+            issues: vec![
+                issue(12, Some("https://github.com/owner/second/issues/12")),
+                issue(12, Some("https://github.com/owner/first/issues/12")),
+                issue(13, None),
+            ],
+        },
+    ]);
+
+    // Issues of unknown repositories are never de-duplicated
+    assert_eq!(duplicates, 1);
+    assert_eq!(
+        repository
+            .issues
+            .iter()
+            .map(|issue| issue.repository())
+            .collect::<Vec<Option<String>>>(),
+        vec![
+            Some(String::from("owner/first")),
+            None,
+            Some(String::from("owner/second")),
+            None,
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_json_lines() -> Result<(), Box<dyn std::error::Error>> {
+    let repository: Repository = parse_input(
+        "tests/doc/teobiton-issue-tracking.jsonl",
+        &InputFormat::Auto,
+    )?;
+
+    assert_eq!(repository.issues.len(), 3);
+    assert_eq!(
+        repository.issues[2].title,
+        "Introduce filtering capabilities"
+    );
+
+    // A JSON document cannot be read as JSON Lines
+    assert!(parse_input(
+        "tests/doc/teobiton-issue-tracking.json",
+        &InputFormat::JsonLines
+    )
+    .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_csv_export() -> Result<(), Box<dyn std::error::Error>> {
+    let repository: Repository =
+        parse_input("tests/doc/teobiton-issue-tracking.csv", &InputFormat::Auto)?;
+
+    assert_eq!(repository.issues.len(), 3);
+    assert_eq!(repository.issues[1].number, 2);
+    assert_eq!(repository.issues[1].title, "Improve error management");
+    assert_eq!(repository.issues[1].state, IssueState::Closed);
+    assert_eq!(
+        repository.issues[1].updated_at.to_string(),
+        "2023-05-16T00:00:00Z"
+    );
+
+    // Columns chosen with --columns are read back as well
+    let text: &str = "ID,Title,State reason,Labels,Assignees,Author,Comments\n\
+        7,Crash,not_planned,bug; ui,alice; bob,carol,3\n\
+        8,Docs,,,,,0\n";
+    let repository: Repository = parse_csv(text, "columns.csv")?;
+
+    assert_eq!(repository.issues[0].title, "Crash");
+    assert_eq!(
+        repository.issues[0].state_reason,
+        Some(StateReason::NotPlanned)
+    );
+    assert_eq!(repository.issues[0].labels.len(), 2);
+    assert_eq!(repository.issues[0].assignees[1].login, "bob");
+    assert_eq!(repository.issues[0].comments.count(), 3);
+    assert!(repository.issues[1].user.is_none());
+    assert!(parse_csv("ID,Comments\n7,many\n", "columns.csv").is_err());
+
+    // Columns are also recognised by name, unknown ones are ignored
+    let text: &str = "id,title,created,repository,notes\n\
+        7,Crash,2023-05-14,teobiton/issue-tracking,skip\n";
+    let repository: Repository = parse_csv(text, "names.csv")?;
+
+    assert_eq!(repository.issues[0].title, "Crash");
+    assert_eq!(
+        repository.issues[0].created_at.to_string(),
+        "2023-05-14T00:00:00Z"
+    );
+    assert_eq!(
+        repository.issues[0].repository(),
+        Some(String::from("teobiton/issue-tracking"))
+    );
+
+    // A csv file without issue numbers is not an export of the tool
+    assert!(parse_input("tests/doc/teobiton-issue-tracking.json", &InputFormat::Csv).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_collect_repo_labels() -> Result<(), Box<dyn std::error::Error>> {
+    let json_file = Path::new("tests/doc/teobiton-issue-tracking.json");
+    let repository: Repository = parse_json_input(json_file)?;
+
+    let labels: Vec<LabelStats> = collect_repo_labels(&repository.issues, "name");
+
+    assert_eq!(labels.len(), 2);
+    assert_eq!(labels[0].name, "enhancement");
+    assert_eq!(labels[0].color, Some(String::from("a2eeef")));
+    assert_eq!(labels[0].open, 1);
+    assert_eq!(labels[0].closed, 1);
+    assert_eq!(labels[1].name, "feature");
+    assert_eq!(labels[1].total(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_label_map() -> Result<(), Box<dyn std::error::Error>> {
+    let map: LabelMap = LabelMap::from_file("tests/doc/label-map.json")?;
+
+    assert_eq!(
+        map.map_label("enhancement"),
+        Some(String::from("type:feature"))
+    );
+    assert_eq!(map.map_label("feat"), Some(String::from("type:feature")));
+    assert_eq!(map.map_label("duplicate"), None);
+    assert_eq!(map.map_label("bug"), Some(String::from("bug")));
+
+    // Both labels are merged into the same one
+    let mut repository: Repository =
+        parse_json_input(Path::new("tests/doc/teobiton-issue-tracking.json"))?;
+    repository.issues[0].labels.push(Label {
+        name: String::from("feature"),
+        ..Default::default()
+    });
+
+    map.apply(&mut repository);
+
+    assert_eq!(repository.issues[0].labels.len(), 1);
+    assert_eq!(repository.issues[0].labels[0].name, "type:feature");
+
+    // Rules must have a single matcher and a single action
+    assert!(LabelMap::from_json(
+        r#"{"rules": [{"match": "a", "regex": "b", "drop": true}]}"#,
+        "map"
+    )
+    .is_err());
+    assert!(LabelMap::from_json(r#"{"rules": [{"match": "a"}]}"#, "map").is_err());
+    assert!(LabelMap::from_json(r#"{"rules": [{"regex": "(", "drop": true}]}"#, "map").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_gh_cli_json() -> Result<(), Box<dyn std::error::Error>> {
+    // The GitHub CLI exports the body and the content of the comments
+    let repository: Repository = parse_input("tests/doc/gh-cli-export.json", &InputFormat::Auto)?;
+
+    assert_eq!(repository.issues.len(), 2);
+    assert_eq!(repository.issues[0].comments.count(), 2);
+    assert_eq!(repository.issues[0].state, IssueState::Open);
+    assert_eq!(repository.issues[1].state, IssueState::Closed);
+    assert_eq!(repository.issues[0].texts().len(), 4);
+    assert_eq!(
+        repository.issues[1].closed_at,
+        Some(Timestamp::parse("2023-06-04T11:30:00Z")?)
+    );
+
+    // The GitHub API only provides the number of comments
+    let repository: Repository =
+        parse_input("tests/doc/teobiton-issue-tracking.json", &InputFormat::Auto)?;
+
+    assert_eq!(repository.issues[0].comments.count(), 0);
+    assert_eq!(repository.issues[0].state_reason, None);
+    assert_eq!(
+        repository.issues[1].state_reason,
+        Some(StateReason::Completed)
+    );
+    assert!(repository.issues[0]
+        .body
+        .as_deref()
+        .unwrap_or_default()
+        .starts_with("Rather than using crate StructOpt"));
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_reactions() -> Result<(), Box<dyn std::error::Error>> {
+    // Reactions default to zero when the summary is missing
+    let repository: Repository = parse_input("tests/doc/reactions.jsonl", &InputFormat::Auto)?;
+    let issues = &repository.issues;
+
+    assert_eq!(issues[0].reactions.total_count, 14);
+    assert_eq!(issues[0].reactions.get("+1"), Some(12));
+    assert_eq!(issues[1].reactions.get("heart"), Some(3));
+    assert_eq!(issues[1].reactions.get("-1"), Some(1));
+    assert_eq!(issues[2].reactions.total_count, 0);
+    assert_eq!(issues[0].reactions.get("thumbs"), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_sort_issues() -> Result<(), Box<dyn std::error::Error>> {
+    let mut issues = parse_input("tests/doc/reactions.jsonl", &InputFormat::Auto)?.issues;
+
+    // Counts are sorted from the highest by default, other keys from the lowest
+    let cases: [(&str, [i32; 3]); 6] = [
+        ("comments", [22, 21, 23]),
+        ("reactions", [21, 22, 23]),
+        ("heart", [22, 21, 23]),
+        ("+1:asc", [23, 22, 21]),
+        ("created:desc", [23, 22, 21]),
+        ("number", [21, 22, 23]),
+    ];
+
+    for (key, expected) in cases {
+        IssueSort::parse(key)?.sort(&mut issues);
+        let numbers: Vec<i32> = issues.iter().map(|issue| issue.number).collect();
+        assert_eq!(numbers, expected, "{}", key);
+    }
+
+    assert!(IssueSort::parse("comments:up").is_err());
+    assert!(IssueSort::parse("title").is_err());
+    assert!(IssueSort::parse("comment")
+        .unwrap_err()
+        .to_string()
+        .contains("Did you mean 'comments'?"));
+
+    Ok(())
+}
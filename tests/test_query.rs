@@ -0,0 +1,185 @@
+use issue_tracking::date::Date;
+use issue_tracking::date::Timestamp;
+use issue_tracking::filters::Filter;
+use issue_tracking::filters::Filters;
+use issue_tracking::parser::Comments;
+use issue_tracking::parser::Issue;
+use issue_tracking::parser::IssueState;
+use issue_tracking::parser::Label;
+use issue_tracking::parser::Milestone;
+use issue_tracking::parser::Reactions;
+use issue_tracking::parser::User;
+use issue_tracking::query::Query;
+use issue_tracking::timezone::TimeZone;
+
+/*
+   Tests for GitHub-style queries.
+*/
+
+fn issues() -> Result<[Issue; 4], Box<dyn std::error::Error>> {
+    let label = |name: &str| Label {
+        name: String::from(name),
+        ..Default::default()
+    };
+    let user = |login: &str| User {
+        login: String::from(login),
+    };
+
+    Ok([
+        Issue {
+            number: 1,
+            title: String::from("Panic in the csv writer"),
+            state: IssueState::Open,
+            labels: vec![label("bug")],
+            user: Some(user("alice")),
+            assignees: vec![user("bob")],
+            created_at: Timestamp::parse("2023-02-10T10:00:00Z")?,
+            updated_at: Timestamp::parse("2023-03-01T10:00:00Z")?,
+            comments: Comments::Count(4),
+            ..Default::default()
+        },
+        Issue {
+            number: 2,
+            title: String::from("Crash on empty labels"),
+            body: Some(String::from("The parser ends with a panic in serde.")),
+            reactions: Reactions {
+                total_count: 7,
+                ..Default::default()
+            },
+            state: IssueState::Open,
+            labels: vec![label("bug"), label("wontfix")],
+            user: Some(user("alice")),
+            created_at: Timestamp::parse("2023-04-02T10:00:00Z")?,
+            updated_at: Timestamp::parse("2023-07-01T10:00:00Z")?,
+            ..Default::default()
+        },
+        Issue {
+            number: 3,
+            title: String::from("Support JSON Lines"),
+            state: IssueState::Closed,
+            labels: vec![label("enhancement")],
+            user: Some(user("carol")),
+            milestone: Some(Milestone {
+                title: String::from("v1.0"),
+            }),
+            created_at: Timestamp::parse("2022-11-20T10:00:00Z")?,
+            updated_at: Timestamp::parse("2023-01-15T10:00:00Z")?,
+            closed_at: Some(Timestamp::parse("2023-01-15T10:00:00Z")?),
+            comments: Comments::Count(12),
+            ..Default::default()
+        },
+        Issue {
+            number: 4,
+            title: String::from("Document the options"),
+            state: IssueState::Open,
+            created_at: Timestamp::parse("2023-05-05T10:00:00Z")?,
+            updated_at: Timestamp::parse("2023-05-06T10:00:00Z")?,
+            ..Default::default()
+        },
+    ])
+}
+
+fn matching(query: &str) -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+    // Numbers of the issues matched by the query
+    let now: Date = Date::parse("2023-08-01")?;
+    let query: Query = Query::parse(query, &now, false)?;
+
+    Ok(issues()?
+        .iter()
+        .filter(|issue| query.matches(issue, &TimeZone::utc()))
+        .map(|issue| issue.number)
+        .collect())
+}
+
+#[test]
+fn test_query_qualifiers() -> Result<(), Box<dyn std::error::Error>> {
+    let cases: [(&str, Vec<i32>); 14] = [
+        ("is:open", vec![1, 2, 4]),
+        ("is:closed", vec![3]),
+        ("label:bug -label:wontfix", vec![1]),
+        ("author:alice", vec![1, 2]),
+        ("author:ALICE is:open -label:wontfix", vec![1]),
+        ("assignee:bob", vec![1]),
+        ("no:assignee no:milestone", vec![2, 4]),
+        ("no:label", vec![4]),
+        ("milestone:\"v1.0\"", vec![3]),
+        ("created:>2023-01-01", vec![1, 2, 4]),
+        ("created:>2023-02", vec![2, 4]),
+        ("updated:2023-01..2023-06", vec![1, 3, 4]),
+        ("closed:<=2023 comments:>=10", vec![3]),
+        ("\"panic in\"", vec![1, 2]),
+    ];
+
+    for (query, expected) in cases {
+        assert_eq!(matching(query)?, expected, "{}", query);
+    }
+
+    // Ranges can be open-ended and relative to the reference date
+    assert_eq!(matching("created:*..2022-12-31")?, vec![3]);
+    assert_eq!(matching("updated:>=31d")?, vec![2]);
+    assert_eq!(matching("comments:1..5")?, vec![1]);
+    assert_eq!(matching("reactions:>5")?, vec![2]);
+    assert_eq!(matching("-\"panic in\" -is:closed")?, vec![4]);
+
+    Ok(())
+}
+
+#[test]
+fn test_milestone_case() -> Result<(), Box<dyn std::error::Error>> {
+    // The --milestone filter and the 'milestone:' qualifier both ignore case
+    for title in ["v1.0", "V1.0"] {
+        let filters: Filters = Filters::builder()
+            .milestones(&[String::from(title)])
+            .build();
+        let filtered: Vec<i32> = issues()?
+            .iter()
+            .filter(|issue| filters.accept(issue))
+            .map(|issue| issue.number)
+            .collect();
+
+        assert_eq!(filtered, vec![3], "{}", title);
+        assert_eq!(
+            matching(&format!("milestone:{}", title))?,
+            vec![3],
+            "{}",
+            title
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_query_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let now: Date = Date::parse("2023-08-01")?;
+
+    let queries: [&str; 10] = [
+        "is:merged",
+        "label:",
+        "reviewer:alice",
+        "created:>2023-02-30",
+        "comments:many",
+        "comments:>9223372036854775807",
+        "reactions:<-9223372036854775808",
+        "comments:>=99999999999999999999",
+        "no:reviewer",
+        "\"panic in",
+    ];
+
+    for query in queries {
+        assert!(Query::parse(query, &now, false).is_err(), "{}", query);
+    }
+
+    // The parsed query can be displayed for debugging
+    assert_eq!(
+        Query::parse(
+            "is:open -label:wontfix created:2023-01..2023-06",
+            &now,
+            false
+        )?
+        .to_string(),
+        "state = open AND NOT label = 'wontfix' AND created in 2023-01-01..2023-06-30"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use issue_tracking::date::Date;
+use issue_tracking::date::Timestamp;
+use issue_tracking::timezone::TimeZone;
+
+/*
+   Tests for time zones and local dates.
+*/
+
+#[test]
+fn test_fixed_offsets() -> Result<(), Box<dyn std::error::Error>> {
+    let offsets: [(&str, i32); 6] = [
+        ("UTC", 0),
+        ("+02:00", 7200),
+        ("-0530", -19800),
+        ("+9", 32400),
+        ("UTC-03:00", -10800),
+        ("GMT+01", 3600),
+    ];
+
+    for (name, offset) in offsets {
+        assert_eq!(TimeZone::parse(name)?.offset_at(0), offset, "{}", name);
+    }
+
+    assert!(TimeZone::parse("+25:00").is_err());
+    assert!(TimeZone::parse("../etc/passwd").is_err());
+    assert!(TimeZone::parse("Nowhere/City").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_posix_rules() -> Result<(), Box<dyn std::error::Error>> {
+    let paris: TimeZone = TimeZone::from_posix("Paris", "CET-1CEST,M3.5.0,M10.5.0/3")?;
+    let sydney: TimeZone = TimeZone::from_posix("Sydney", "AEST-10AEDT,M10.1.0,M4.1.0/3")?;
+
+    // Daylight saving time starts on 2023-03-26 at 01:00 UTC and ends on 2023-10-29 at 01:00 UTC
+    let instants: [(&str, i32); 4] = [
+        ("2023-03-26T00:59:59Z", 3600),
+        ("2023-03-26T01:00:00Z", 7200),
+        ("2023-10-29T00:59:59Z", 7200),
+        ("2023-10-29T01:00:00Z", 3600),
+    ];
+
+    for (instant, offset) in instants {
+        let seconds: i64 = Timestamp::parse(instant)?.seconds();
+        assert_eq!(paris.offset_at(seconds), offset, "{}", instant);
+    }
+
+    // Daylight saving time spans the new year in the southern hemisphere
+    let summer: i64 = Timestamp::parse("2024-01-15T00:00:00Z")?.seconds();
+    let winter: i64 = Timestamp::parse("2024-06-15T00:00:00Z")?.seconds();
+
+    assert_eq!(sydney.offset_at(summer), 39600);
+    assert_eq!(sydney.offset_at(winter), 36000);
+
+    assert!(TimeZone::from_posix("Bogus", "CET-1CEST,M13.5.0,M10.5.0").is_err());
+
+    // Hours up to one week are accepted, larger values and overflows are not
+    assert!(TimeZone::from_posix("Week", "XXX-167").is_ok());
+    assert!(TimeZone::from_posix("Bogus", "XXX-168").is_err());
+    assert!(TimeZone::from_posix("Bogus", "XXX-1:99999999").is_err());
+    assert!(TimeZone::from_posix("Bogus", "XXX-99999999").is_err());
+
+    // Julian days count from 1 and ordinal days from 0, both up to 365
+    assert!(TimeZone::from_posix("Julian", "CET-1CEST,J1,J365").is_ok());
+    assert!(TimeZone::from_posix("Bogus", "CET-1CEST,J0,J365").is_err());
+    assert!(TimeZone::from_posix("Ordinal", "CET-1CEST,0,365").is_ok());
+    assert!(TimeZone::from_posix("Bogus", "CET-1CEST,0,366").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_zoneinfo_dates() -> Result<(), Box<dyn std::error::Error>> {
+    let paris: TimeZone = TimeZone::parse("Europe/Paris")?;
+
+    // Closed at 23:30 in Paris, which is the next day in UTC during winter
+    let closed: Timestamp = Timestamp::parse("2023-01-31T22:30:00Z")?;
+    assert_eq!(closed.utc_date(), Date::parse("2023-01-31")?);
+    assert_eq!(closed.date_in(&paris), Date::parse("2023-01-31")?);
+
+    let closed: Timestamp = Timestamp::parse("2023-06-30T22:30:00Z")?;
+    assert_eq!(closed.date_in(&paris), Date::parse("2023-07-01")?);
+    assert_eq!(
+        closed.in_zone(&paris).to_string(),
+        "2023-07-01T00:30:00+02:00"
+    );
+
+    // Far in the future, offsets come from the rule at the end of the file
+    let future: i64 = Timestamp::parse("2100-07-01T00:00:00Z")?.seconds();
+    assert_eq!(paris.offset_at(future), 7200);
+
+    // Dates without time are not shifted
+    let date_only: Timestamp = Timestamp::parse("2023-06-30")?;
+    assert_eq!(
+        date_only.date_in(&TimeZone::parse("-10:00")?),
+        Date::parse("2023-06-30")?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_zoneinfo() {
+    // Header with the given version and counts, without the data they announce
+    let header = |version: u8, counts: [u32; 6]| -> Vec<u8> {
+        let mut bytes: Vec<u8> = b"TZif".to_vec();
+        bytes.push(version);
+        bytes.extend_from_slice(&[0; 15]);
+        for count in counts {
+            bytes.extend_from_slice(&count.to_be_bytes());
+        }
+        bytes
+    };
+
+    let files: [Vec<u8>; 5] = [
+        b"TZif2".to_vec(),
+        header(0, [0, 0, 0, 0, 0, 0]),
+        header(0, [0, 0, 0, u32::MAX, 1, 0]),
+        header(b'2', [0, 0, 0, 0, 1, 4]),
+        header(b'2', [u32::MAX; 6]),
+    ];
+
+    for bytes in files {
+        assert!(TimeZone::from_tzif("Bogus/Zone", &bytes).is_err());
+    }
+}
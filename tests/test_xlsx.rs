@@ -0,0 +1,123 @@
+use issue_tracking::columns::Value;
+use issue_tracking::date::Date;
+use issue_tracking::xlsx::build_workbook;
+use issue_tracking::xlsx::column_name;
+use issue_tracking::xlsx::escape;
+use issue_tracking::xlsx::serial_date;
+use issue_tracking::xlsx::sheet_name;
+use issue_tracking::xlsx::Sheet;
+use issue_tracking::zip::crc32;
+use issue_tracking::zip::ZipArchive;
+
+/*
+   Tests for XLSX workbooks and the zip archives they are stored in.
+*/
+
+fn contains(data: &[u8], text: &str) -> bool {
+    // Files are stored without compression, so their content can be found in the archive
+    data.windows(text.len())
+        .any(|window| window == text.as_bytes())
+}
+
+#[test]
+fn test_zip_archive() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF43926);
+
+    let mut archive: ZipArchive = ZipArchive::new();
+    archive.add_file("a.txt", b"hello")?;
+    archive.add_file("dir/b.txt", b"world!")?;
+    let data: Vec<u8> = archive.finish()?;
+
+    // Local header, then the name and the content of the first file
+    assert_eq!(&data[0..4], b"PK\x03\x04");
+    assert_eq!(&data[14..18], &crc32(b"hello").to_le_bytes());
+    assert_eq!(&data[30..40], b"a.txthello");
+
+    // The end record counts the files and points to the central directory
+    let end: &[u8] = &data[data.len() - 22..];
+    assert_eq!(&end[0..4], b"PK\x05\x06");
+    assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+
+    let offset: usize = u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize;
+    assert_eq!(&data[offset..offset + 4], b"PK\x01\x02");
+
+    // Names and file counts beyond the limits of the headers are rejected, without ZIP64
+    let mut archive: ZipArchive = ZipArchive::new();
+    assert!(archive.add_file(&"a".repeat(65536), b"").is_err());
+
+    for n in 0..65535 {
+        archive.add_file(&n.to_string(), b"")?;
+    }
+    assert!(archive.add_file("full.txt", b"").is_err());
+    assert!(archive.finish().is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_workbook_helpers() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(column_name(0), "A");
+    assert_eq!(column_name(25), "Z");
+    assert_eq!(column_name(26), "AA");
+    assert_eq!(column_name(701), "ZZ");
+    assert_eq!(column_name(702), "AAA");
+
+    assert_eq!(serial_date(&Date::parse("1970-01-01")?), 25569);
+    assert_eq!(serial_date(&Date::parse("2023-05-15")?), 45061);
+
+    assert_eq!(escape("a < b & \"c\"\u{1}"), "a &lt; b &amp; &quot;c&quot;");
+
+    let used: Vec<String> = vec![String::from("Issues"), String::from("area_ui")];
+    assert_eq!(sheet_name("area:ui", &[]), "area_ui");
+    assert_eq!(sheet_name("AREA/UI", &used), "AREA_UI (2)");
+    assert_eq!(sheet_name("'quoted'", &used), "quoted");
+    assert_eq!(sheet_name("", &used), "Sheet");
+    assert_eq!(sheet_name(&"x".repeat(40), &[]).len(), 31);
+    assert_eq!(
+        sheet_name(&"x".repeat(40), &["x".repeat(31)]),
+        format!("{} (2)", "x".repeat(27))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_build_workbook() -> Result<(), Box<dyn std::error::Error>> {
+    let sheets: [Sheet; 2] = [
+        Sheet {
+            name: String::from("Issues"),
+            rows: vec![vec![
+                Value::Integer(7),
+                Value::Text(String::from("Crash <now>")),
+                Value::Date(Date::parse("2023-05-15")?),
+                Value::Empty,
+            ]],
+        },
+        Sheet {
+            name: String::from("bug"),
+            rows: vec![],
+        },
+    ];
+
+    let data: Vec<u8> = build_workbook(&["ID", "Title", "Created at", "Closed at"], &sheets)?;
+
+    assert!(contains(&data, "xl/worksheets/sheet2.xml"));
+    assert!(contains(
+        &data,
+        r#"<sheet name="bug" sheetId="2" r:id="rId2"/>"#
+    ));
+
+    // Typed cells, a frozen header and an auto-filter
+    assert!(contains(&data, r#"<c r="A2"><v>7</v></c>"#));
+    assert!(contains(
+        &data,
+        r#"<c r="B2" t="inlineStr"><is><t xml:space="preserve">Crash &lt;now&gt;</t></is></c>"#
+    ));
+    assert!(contains(&data, r#"<c r="C2" s="1"><v>45061</v></c></row>"#));
+    assert!(contains(&data, r#"state="frozen""#));
+    assert!(contains(&data, r#"<autoFilter ref="A1:D2"/>"#));
+    assert!(contains(&data, r#"<autoFilter ref="A1:D1"/>"#));
+
+    Ok(())
+}